@@ -0,0 +1,20 @@
+use anchor_lang::AccountDeserialize;
+
+pub use card::state::{CardRegistry, Deck, Game, Leaderboard, Season, SeasonArchive, UserStats};
+
+// Decodes raw account data, discriminator included, into any of the program's accounts
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> anchor_lang::Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_game(data: &[u8]) -> anchor_lang::Result<Game> {
+    decode(data)
+}
+
+pub fn decode_user_stats(data: &[u8]) -> anchor_lang::Result<UserStats> {
+    decode(data)
+}
+
+pub fn decode_leaderboard(data: &[u8]) -> anchor_lang::Result<Leaderboard> {
+    decode(data)
+}
@@ -0,0 +1,292 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use card::{accounts, instruction};
+use card::state::{AttackTarget, Card, EffectTarget};
+use crate::pda::*;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: card::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// game has to sign as well, it is created by this instruction
+// commitment is the head of p1's seed_chain, grown from a secret p1 keeps for the whole game
+pub fn create_challenge(game: Pubkey, p1: Pubkey, p2: Pubkey, deck_index: u8, turn_limit: i64, commitment: [u8; 32]) -> Instruction {
+    build(
+        accounts::CreateChallenge {
+            game,
+            p1,
+            p1_stats: user_stats_pda(&p1).0,
+            p2,
+            p2_stats: user_stats_pda(&p2).0,
+            p1_deck: deck_pda(&p1, deck_index).0,
+            card_registry: card_registry_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateChallenge { turn_limit, commitment },
+    )
+}
+
+pub fn accept_challenge(game: Pubkey, p1: Pubkey, p2: Pubkey, deck_index: u8, commitment: [u8; 32]) -> Instruction {
+    build(
+        accounts::AcceptChallenge {
+            game,
+            p1,
+            p2,
+            p2_stats: user_stats_pda(&p2).0,
+            p2_deck: deck_pda(&p2, deck_index).0,
+            card_registry: card_registry_pda().0,
+        },
+        instruction::AcceptChallenge { commitment },
+    )
+}
+
+// link is the next one down the player's seed chain, the first one before the game starts
+pub fn reveal_seed(game: Pubkey, player: Pubkey, link: [u8; 32]) -> Instruction {
+    build(accounts::RevealSeed { game, player }, instruction::RevealSeed { link })
+}
+
+pub fn cancel_challenge(game: Pubkey, p1: Pubkey, p2: Pubkey) -> Instruction {
+    build(
+        accounts::CancelChallenge {
+            game,
+            p1,
+            p1_stats: user_stats_pda(&p1).0,
+            p2,
+            p2_stats: user_stats_pda(&p2).0,
+        },
+        instruction::CancelChallenge {},
+    )
+}
+
+pub fn decline_challenge(game: Pubkey, p1: Pubkey, p2: Pubkey) -> Instruction {
+    build(
+        accounts::DeclineChallenge {
+            game,
+            p1,
+            p1_stats: user_stats_pda(&p1).0,
+            p2,
+            p2_stats: user_stats_pda(&p2).0,
+        },
+        instruction::DeclineChallenge {},
+    )
+}
+
+pub fn play_card(game: Pubkey, player: Pubkey, pos: u8, card_index: u8, target: Option<EffectTarget>) -> Instruction {
+    build(
+        accounts::PlayCard { game, player },
+        instruction::PlayCard { pos, card_index, target },
+    )
+}
+
+pub fn cast_spell(game: Pubkey, player: Pubkey, card_index: u8, target_row: u8, target_pos: u8) -> Instruction {
+    build(
+        accounts::CastSpell { game, player },
+        instruction::CastSpell { card_index, target_row, target_pos },
+    )
+}
+
+// Ending turn n takes link n + 1 of the player's seed chain, the opponent reveals the same link of theirs to start their turn
+pub fn end_turn(game: Pubkey, player: Pubkey, link: [u8; 32]) -> Instruction {
+    build(accounts::EndTurn { game, player }, instruction::EndTurn { link })
+}
+
+pub fn attack(game: Pubkey, player: Pubkey, ally_pos: u8, target: AttackTarget) -> Instruction {
+    build(
+        accounts::Attack { game, player },
+        instruction::Attack { ally_pos, target },
+    )
+}
+
+pub fn init_leaderboard(user: Pubkey) -> Instruction {
+    let (leaderboard, bump) = leaderboard_pda();
+    build(
+        accounts::InitLeaderboard { user, leaderboard, system_program: system_program::ID },
+        instruction::InitLeaderboard { bump },
+    )
+}
+
+// Meant to be simulated, the page comes back as return data
+pub fn get_leaderboard(offset: u32, limit: u8) -> Instruction {
+    build(
+        accounts::GetLeaderboard { leaderboard: leaderboard_pda().0 },
+        instruction::GetLeaderboard { offset, limit },
+    )
+}
+
+// admin has to be the program's upgrade authority
+pub fn init_season(admin: Pubkey, duration: i64) -> Instruction {
+    build(
+        accounts::InitSeason {
+            admin,
+            program_data: program_data_pda().0,
+            season: season_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitSeason { duration },
+    )
+}
+
+// season_number is the number of the season being closed, which names its archive
+pub fn rollover_season(admin: Pubkey, season_number: u32) -> Instruction {
+    build(
+        accounts::RolloverSeason {
+            admin,
+            season: season_pda().0,
+            season_archive: season_archive_pda(season_number).0,
+            leaderboard: leaderboard_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::RolloverSeason {},
+    )
+}
+
+pub fn create_user_stats(user: Pubkey, name: String) -> Instruction {
+    build(
+        accounts::CreateUserStats {
+            user,
+            user_stats: user_stats_pda(&user).0,
+            leaderboard: leaderboard_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateUserStats { name },
+    )
+}
+
+// admin has to be the program's upgrade authority, it gets back the rent the smaller account no longer needs
+pub fn migrate_leaderboard(admin: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateLeaderboard {
+            admin,
+            program_data: program_data_pda().0,
+            leaderboard: leaderboard_pda().0,
+        },
+        instruction::MigrateLeaderboard {},
+    )
+}
+
+// Anyone can pay to bring a player's stats over from the layout before ratings
+pub fn migrate_user_stats(payer: Pubkey, player: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateUserStats {
+            payer,
+            player,
+            user_stats: user_stats_pda(&player).0,
+            leaderboard: leaderboard_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUserStats {},
+    )
+}
+
+// admin has to be the program's upgrade authority
+pub fn init_card_registry(admin: Pubkey) -> Instruction {
+    build(
+        accounts::InitCardRegistry {
+            admin,
+            program_data: program_data_pda().0,
+            card_registry: card_registry_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitCardRegistry {},
+    )
+}
+
+pub fn add_card(admin: Pubkey, card: Card) -> Instruction {
+    build(
+        accounts::AddCard { admin, card_registry: card_registry_pda().0 },
+        instruction::AddCard { card },
+    )
+}
+
+pub fn update_card(admin: Pubkey, id: u16, card: Card) -> Instruction {
+    build(
+        accounts::UpdateCard { admin, card_registry: card_registry_pda().0 },
+        instruction::UpdateCard { id, card },
+    )
+}
+
+pub fn create_deck(owner: Pubkey, index: u8, cards: Vec<u16>) -> Instruction {
+    build(
+        accounts::CreateDeck {
+            owner,
+            deck: deck_pda(&owner, index).0,
+            card_registry: card_registry_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateDeck { index, cards },
+    )
+}
+
+pub fn update_deck(owner: Pubkey, index: u8, cards: Vec<u16>) -> Instruction {
+    build(
+        accounts::UpdateDeck {
+            owner,
+            deck: deck_pda(&owner, index).0,
+            card_registry: card_registry_pda().0,
+        },
+        instruction::UpdateDeck { cards },
+    )
+}
+
+pub fn set_scores(game: Pubkey, p1: Pubkey, p2: Pubkey) -> Instruction {
+    build(
+        accounts::SetScores {
+            p1,
+            p1_stats: user_stats_pda(&p1).0,
+            p2,
+            p2_stats: user_stats_pda(&p2).0,
+            game,
+            leaderboard: leaderboard_pda().0,
+            season: season_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::SetScores {},
+    )
+}
+
+// player is the one giving up the game
+pub fn concede(game: Pubkey, player: Pubkey, opponent: Pubkey) -> Instruction {
+    build(
+        accounts::Concede {
+            game,
+            player,
+            player_stats: user_stats_pda(&player).0,
+            opponent,
+            opponent_stats: user_stats_pda(&opponent).0,
+            leaderboard: leaderboard_pda().0,
+            season: season_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::Concede {},
+    )
+}
+
+// player is the one claiming the win, opponent the one who ran out of time
+pub fn claim_timeout(game: Pubkey, player: Pubkey, opponent: Pubkey) -> Instruction {
+    build(
+        accounts::ClaimTimeout {
+            game,
+            player,
+            player_stats: user_stats_pda(&player).0,
+            opponent,
+            opponent_stats: user_stats_pda(&opponent).0,
+            leaderboard: leaderboard_pda().0,
+            season: season_pda().0,
+        },
+        instruction::ClaimTimeout {},
+    )
+}
+
+// The rent goes to p1 whichever player closes the game
+pub fn close_game(game: Pubkey, p1: Pubkey, player: Pubkey) -> Instruction {
+    build(
+        accounts::CloseGame { game, p1, player },
+        instruction::CloseGame {},
+    )
+}
@@ -0,0 +1,15 @@
+// Rust client for the card program, built on the program's own types so it can't drift from them
+// Decoders hand back anchor's own error type, which is large but what every caller already matches on
+#![allow(clippy::result_large_err)]
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+
+pub use accounts::*;
+pub use instructions::*;
+pub use pda::*;
+
+pub use card::state::{AttackTarget, Card, CardKind, Effect, Aura, EffectTarget, GameState, LeaderboardEntry};
+pub use card::state::{combine_seeds, seed_chain, seed_commitment, SEED_CHAIN_LENGTH};
+pub use card::ID as PROGRAM_ID;
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+pub fn user_stats_pda(player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user-stats", player.as_ref()], &card::ID)
+}
+
+pub fn leaderboard_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"leaderboard"], &card::ID)
+}
+
+pub fn card_registry_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"card-registry"], &card::ID)
+}
+
+pub fn deck_pda(owner: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"deck", owner.as_ref(), &[index]], &card::ID)
+}
+
+pub fn season_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"season"], &card::ID)
+}
+
+pub fn season_archive_pda(number: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"season-archive", &number.to_le_bytes()], &card::ID)
+}
+
+// Owned by the upgradeable loader, holds the program's upgrade authority
+pub fn program_data_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[card::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
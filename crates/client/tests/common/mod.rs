@@ -0,0 +1,254 @@
+// Shared setup for the in-process program tests, every test starts from a fresh bank
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use card::errors::CardGameError;
+use card::state::{LegacyLeaderboard, LegacyUserStats};
+use card_client::*;
+use solana_program_test::*;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::clock::Clock;
+use solana_sdk::hash::hashv;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const FOOTMAN_ID: u16 = 0;
+pub const SHIELDBEARER_ID: u16 = 1;
+pub const RAIDER_ID: u16 = 2;
+pub const BOLT_ID: u16 = 3;
+pub const BLESSING_ID: u16 = 4;
+pub const ARCHER_ID: u16 = 5;
+
+pub const TURN_LIMIT: i64 = 60;
+pub const SEASON_DURATION: i64 = 30 * 24 * 60 * 60;
+
+pub fn unit(hp: i8, atk: i8, mana: i8) -> Card {
+    Card {
+        hp,
+        max_hp: hp,
+        atk,
+        mana,
+        moves: 0,
+        taunt: false,
+        charge: false,
+        rush: false,
+        summoning_sick: false,
+        divine_shield: false,
+        kind: CardKind::Unit,
+        battlecry: None,
+        deathrattle: None,
+        aura: None,
+        aura_atk: 0,
+        aura_hp: 0,
+    }
+}
+
+pub fn spell(effect: Effect, mana: i8) -> Card {
+    Card { kind: CardKind::Spell { effect }, ..unit(0, 0, mana) }
+}
+
+// Registry entries in id order
+pub fn test_cards() -> Vec<Card> {
+    vec![
+        unit(3, 2, 1),
+        Card { taunt: true, ..unit(4, 1, 1) },
+        Card { charge: true, ..unit(5, 10, 1) },
+        spell(Effect::Damage { amount: 3 }, 1),
+        spell(Effect::Buff { atk: 1, hp: 1 }, 1),
+        Card { battlecry: Some(Effect::Damage { amount: 1 }), ..unit(1, 1, 1) },
+    ]
+}
+
+// A deck of a single card keeps draws predictable
+pub fn deck_of(id: u16) -> Vec<u16> {
+    vec![id; 30]
+}
+
+// With SBF_OUT_DIR (or BPF_OUT_DIR) set, as `cargo test-sbf` does, the compiled card.so found there is run
+// under the real heap and compute limits. Without it the program runs as native code, which has neither
+pub async fn start() -> ProgramTestContext {
+    let sbf = std::env::var("SBF_OUT_DIR").is_ok() || std::env::var("BPF_OUT_DIR").is_ok();
+    let program_test = if sbf {
+        ProgramTest::new("card", card::ID, None)
+    } else {
+        ProgramTest::new("card", card::ID, processor!(card::entry))
+    };
+    let mut ctx = program_test.start_with_context().await;
+    let payer = ctx.payer.pubkey();
+    set_upgrade_authority(&mut ctx, Some(payer));
+    ctx
+}
+
+// Stands in for the program data account a real deploy creates next to the program
+pub fn set_upgrade_authority(ctx: &mut ProgramTestContext, authority: Option<Pubkey>) {
+    let state = UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: authority };
+    let size = UpgradeableLoaderState::size_of_programdata_metadata();
+    let account = AccountSharedData::new_data_with_space(1_000_000_000, &state, size, &bpf_loader_upgradeable::id()).unwrap();
+    ctx.set_account(&program_data_pda().0, &account);
+}
+
+// Stands in for the leaderboard left over from before the top 50, which listed every registered player
+pub fn set_legacy_leaderboard(ctx: &mut ProgramTestContext, players: Vec<Pubkey>) {
+    let legacy = LegacyLeaderboard { players, bump: leaderboard_pda().1 };
+    let mut data = Leaderboard::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(LegacyLeaderboard::ACC_SIZE, 0);
+    let lamports = Rent::default().minimum_balance(data.len());
+    let mut account = AccountSharedData::new(lamports, data.len(), &card::ID);
+    account.set_data(data);
+    ctx.set_account(&leaderboard_pda().0, &account);
+}
+
+// Stands in for a user stats account left over from before ratings, allocated for the old layout
+pub fn set_legacy_user_stats(ctx: &mut ProgramTestContext, player: &Pubkey, legacy: &LegacyUserStats) {
+    let mut data = UserStats::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(8 + LegacyUserStats::MAX_ACC_SIZE, 0);
+    let lamports = Rent::default().minimum_balance(data.len());
+    let mut account = AccountSharedData::new(lamports, data.len(), &card::ID);
+    account.set_data(data);
+    ctx.set_account(&user_stats_pda(player).0, &account);
+}
+
+// Moves to the next slot first so repeating an identical instruction gets a fresh blockhash
+pub async fn send(ctx: &mut ProgramTestContext, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let slot = ctx.banks_client.get_root_slot().await?;
+    ctx.warp_to_slot(slot + 1).unwrap();
+    let blockhash = ctx.banks_client.get_latest_blockhash().await?;
+
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, expected: CardGameError) {
+    let code: u32 = expected.into();
+    match result {
+        Err(err) => assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            "expected {}",
+            expected
+        ),
+        Ok(()) => panic!("expected {} but the transaction succeeded", expected),
+    }
+}
+
+// For failures raised by anchor's account checks rather than the program
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, expected: anchor_lang::error::ErrorCode) {
+    let code: u32 = expected.into();
+    match result {
+        Err(err) => assert_eq!(
+            err.unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            "expected {}",
+            expected
+        ),
+        Ok(()) => panic!("expected {} but the transaction succeeded", expected),
+    }
+}
+
+pub async fn fetch<T: AccountDeserialize>(ctx: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = ctx.banks_client.get_account(address).await.unwrap().expect("account missing");
+    decode(&account.data).unwrap()
+}
+
+pub async fn account_exists(ctx: &mut ProgramTestContext, address: Pubkey) -> bool {
+    ctx.banks_client.get_account(address).await.unwrap().is_some()
+}
+
+pub async fn lamports(ctx: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    ctx.banks_client.get_balance(address).await.unwrap()
+}
+
+// Leaderboard, season and card registry, all administered by the payer as the upgrade authority
+pub async fn setup_world(ctx: &mut ProgramTestContext) {
+    let admin = ctx.payer.pubkey();
+    let mut ixs = vec![
+        init_leaderboard(admin),
+        init_season(admin, SEASON_DURATION),
+        init_card_registry(admin),
+    ];
+    ixs.extend(test_cards().into_iter().map(|card| add_card(admin, card)));
+    send(ctx, &ixs, &[]).await.unwrap();
+}
+
+pub async fn fund(ctx: &mut ProgramTestContext, player: &Pubkey) {
+    let ix = system_instruction::transfer(&ctx.payer.pubkey(), player, 1_000_000_000);
+    send(ctx, &[ix], &[]).await.unwrap();
+}
+
+// Funded player with user stats and deck 0
+pub async fn register(ctx: &mut ProgramTestContext, name: &str, deck: Vec<u16>) -> Keypair {
+    let player = Keypair::new();
+    fund(ctx, &player.pubkey()).await;
+    let ixs = [
+        create_user_stats(player.pubkey(), name.to_string()),
+        create_deck(player.pubkey(), 0, deck),
+    ];
+    send(ctx, &ixs, &[&player]).await.unwrap();
+    player
+}
+
+// Secrets only have to stay unguessable until the game is over, in tests each player's is derived from their key
+pub fn secret_of(player: &Pubkey) -> [u8; 32] {
+    hashv(&[b"test-secret", player.as_ref()]).to_bytes()
+}
+
+// Link 0 is the commitment, link 1 is revealed before the game and link n + 1 when turn n ends
+pub fn link_of(player: &Pubkey, index: usize) -> [u8; 32] {
+    seed_chain(player, &secret_of(player))[index]
+}
+
+pub fn commitment_of(player: &Pubkey) -> [u8; 32] {
+    link_of(player, 0)
+}
+
+pub async fn challenge(ctx: &mut ProgramTestContext, p1: &Keypair, p2: &Keypair) -> Keypair {
+    let game = Keypair::new();
+    let ix = create_challenge(game.pubkey(), p1.pubkey(), p2.pubkey(), 0, TURN_LIMIT, commitment_of(&p1.pubkey()));
+    send(ctx, &[ix], &[p1, &game]).await.unwrap();
+    game
+}
+
+pub async fn accept(ctx: &mut ProgramTestContext, game: &Pubkey, p1: &Keypair, p2: &Keypair) {
+    let ix = accept_challenge(*game, p1.pubkey(), p2.pubkey(), 0, commitment_of(&p2.pubkey()));
+    send(ctx, &[ix], &[p2]).await.unwrap();
+}
+
+pub async fn reveal(ctx: &mut ProgramTestContext, game: &Pubkey, player: &Keypair) {
+    let ix = reveal_seed(*game, player.pubkey(), link_of(&player.pubkey(), 1));
+    send(ctx, &[ix], &[player]).await.unwrap();
+}
+
+// Ends turn for the player on it and deals the next one with the opponent's reveal
+pub async fn pass_turn(ctx: &mut ProgramTestContext, game: &Pubkey, turn: u8, from: &Keypair, to: &Keypair) {
+    let link = turn as usize + 1;
+    send(ctx, &[end_turn(*game, from.pubkey(), link_of(&from.pubkey(), link))], &[from]).await.unwrap();
+    send(ctx, &[reveal_seed(*game, to.pubkey(), link_of(&to.pubkey(), link))], &[to]).await.unwrap();
+}
+
+// Challenged, accepted and both seeds revealed, player 1 is on turn
+pub async fn start_game(ctx: &mut ProgramTestContext, p1: &Keypair, p2: &Keypair) -> Keypair {
+    let game = challenge(ctx, p1, p2).await;
+    accept(ctx, &game.pubkey(), p1, p2).await;
+    reveal(ctx, &game.pubkey(), p1).await;
+    reveal(ctx, &game.pubkey(), p2).await;
+    game
+}
+
+pub async fn set_unix_timestamp(ctx: &mut ProgramTestContext, unix_timestamp: i64) {
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    ctx.set_sysvar(&Clock { unix_timestamp, ..clock });
+}
+
+pub async fn unix_timestamp(ctx: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
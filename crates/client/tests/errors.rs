@@ -0,0 +1,362 @@
+// Every reachable CardGameError, each triggered through the instruction that raises it
+//
+// Not covered since no instruction can reach them:
+// EnemyBoardPositionEmpty, AllyBoardPositionEmpty, ScoreTooLow, ScoreTooHigh, UserAlreadyExists,
+// UserStatsAccountMissing, OpposingStatsAccountMissing and GameAlreadyStarted are never raised,
+// CannotAttackOwnHero can't be expressed since an attack only ever targets the enemy hero
+//
+// NotPlayersActiveGame needs a game won on the board, so it is checked in lifecycle.rs
+mod common;
+
+use card::errors::CardGameError;
+use card::state::game::{MAX_TURN_LIMIT, MIN_TURN_LIMIT};
+use card::state::LegacyUserStats;
+use card_client::*;
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn board_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(FOOTMAN_ID)).await;
+    let game = start_game(&mut ctx, &p1, &p2).await.pubkey();
+    let p1_key = p1.pubkey();
+
+    let result = send(&mut ctx, &[play_card(game, p2.pubkey(), 0, 0, None)], &[&p2]).await;
+    assert_error(result, CardGameError::NotPlayersTurn);
+
+    let result = send(&mut ctx, &[play_card(game, p1_key, 7, 0, None)], &[&p1]).await;
+    assert_error(result, CardGameError::TileOutOfBounds);
+
+    let result = send(&mut ctx, &[play_card(game, p1_key, 0, 9, None)], &[&p1]).await;
+    assert_error(result, CardGameError::CardIndexOutOfBounds);
+
+    let out_of_bounds = [
+        (7, AttackTarget::Hero),
+        (255, AttackTarget::Hero),
+        (0, AttackTarget::Unit { pos: 7 }),
+        (0, AttackTarget::Unit { pos: 255 }),
+    ];
+    for (ally_pos, target) in out_of_bounds {
+        let result = send(&mut ctx, &[attack(game, p1_key, ally_pos, target)], &[&p1]).await;
+        assert_error(result, CardGameError::PositionOutOfBounds);
+    }
+
+    let result = send(&mut ctx, &[attack(game, p1_key, 0, AttackTarget::Hero)], &[&p1]).await;
+    assert_error(result, CardGameError::EmptyBoardSpace);
+
+    let result = send(&mut ctx, &[cast_spell(game, p1_key, 0, 0, 0)], &[&p1]).await;
+    assert_error(result, CardGameError::CardIsNotASpell);
+
+    send(&mut ctx, &[play_card(game, p1_key, 0, 0, None)], &[&p1]).await.unwrap();
+
+    let result = send(&mut ctx, &[play_card(game, p1_key, 0, 0, None)], &[&p1]).await;
+    assert_error(result, CardGameError::TileAlreadySet);
+
+    let result = send(&mut ctx, &[play_card(game, p1_key, 1, 0, None)], &[&p1]).await;
+    assert_error(result, CardGameError::InsufficientMana);
+
+    let result = send(&mut ctx, &[attack(game, p1_key, 0, AttackTarget::Hero)], &[&p1]).await;
+    assert_error(result, CardGameError::UnitIsNotReady);
+
+    send(&mut ctx, &[concede(game, p1_key, p2.pubkey())], &[&p1]).await.unwrap();
+
+    let result = send(&mut ctx, &[end_turn(game, p1_key, link_of(&p1_key, 2))], &[&p1]).await;
+    assert_error(result, CardGameError::GameAlreadyOver);
+}
+
+#[tokio::test]
+async fn card_effect_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let raiders = register(&mut ctx, "Raiders", deck_of(RAIDER_ID)).await;
+    let shields = register(&mut ctx, "Shields", deck_of(SHIELDBEARER_ID)).await;
+    let game = start_game(&mut ctx, &raiders, &shields).await.pubkey();
+
+    // A taunt unit on the enemy row has to be attacked first
+    pass_turn(&mut ctx, &game, 1, &raiders, &shields).await;
+    send(&mut ctx, &[play_card(game, shields.pubkey(), 3, 0, None)], &[&shields]).await.unwrap();
+    pass_turn(&mut ctx, &game, 2, &shields, &raiders).await;
+    send(&mut ctx, &[play_card(game, raiders.pubkey(), 0, 0, None)], &[&raiders]).await.unwrap();
+    let result = send(&mut ctx, &[attack(game, raiders.pubkey(), 0, AttackTarget::Hero)], &[&raiders]).await;
+    assert_error(result, CardGameError::MustAttackTauntUnit);
+    send(&mut ctx, &[concede(game, raiders.pubkey(), shields.pubkey())], &[&raiders]).await.unwrap();
+
+    let bolts = register(&mut ctx, "Bolts", deck_of(BOLT_ID)).await;
+    let game = start_game(&mut ctx, &bolts, &shields).await.pubkey();
+    let result = send(&mut ctx, &[play_card(game, bolts.pubkey(), 0, 0, None)], &[&bolts]).await;
+    assert_error(result, CardGameError::SpellCannotBePlaced);
+    send(&mut ctx, &[concede(game, bolts.pubkey(), shields.pubkey())], &[&bolts]).await.unwrap();
+
+    let blessings = register(&mut ctx, "Blessings", deck_of(BLESSING_ID)).await;
+    let game = start_game(&mut ctx, &blessings, &shields).await.pubkey();
+    let result = send(&mut ctx, &[cast_spell(game, blessings.pubkey(), 0, 1, 7)], &[&blessings]).await;
+    assert_error(result, CardGameError::InvalidEffectTarget);
+    send(&mut ctx, &[concede(game, blessings.pubkey(), shields.pubkey())], &[&blessings]).await.unwrap();
+
+    let archers = register(&mut ctx, "Archers", deck_of(ARCHER_ID)).await;
+    let game = start_game(&mut ctx, &archers, &shields).await.pubkey();
+    let result = send(&mut ctx, &[play_card(game, archers.pubkey(), 0, 0, None)], &[&archers]).await;
+    assert_error(result, CardGameError::MissingEffectTarget);
+}
+
+#[tokio::test]
+async fn registration_and_deck_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+
+    let player = Keypair::new();
+    fund(&mut ctx, &player.pubkey()).await;
+    let result = send(&mut ctx, &[create_user_stats(player.pubkey(), "x".repeat(21))], &[&player]).await;
+    assert_error(result, CardGameError::NameTooLong);
+
+    let result = send(&mut ctx, &[create_deck(player.pubkey(), 0, vec![FOOTMAN_ID; 31])], &[&player]).await;
+    assert_error(result, CardGameError::DeckTooLarge);
+
+    let result = send(&mut ctx, &[create_deck(player.pubkey(), 0, vec![99])], &[&player]).await;
+    assert_error(result, CardGameError::UnknownCardId);
+
+    send(&mut ctx, &[create_deck(player.pubkey(), 0, deck_of(FOOTMAN_ID))], &[&player]).await.unwrap();
+    let result = send(&mut ctx, &[update_deck(player.pubkey(), 0, vec![99])], &[&player]).await;
+    assert_error(result, CardGameError::UnknownCardId);
+}
+
+#[tokio::test]
+async fn migration_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let payer = ctx.payer.pubkey();
+
+    // Accounts made in the current layout have nothing to migrate, nor have ones already migrated
+    let intruder = Keypair::new();
+    fund(&mut ctx, &intruder.pubkey()).await;
+    let result = send(&mut ctx, &[migrate_leaderboard(intruder.pubkey())], &[&intruder]).await;
+    assert_error(result, CardGameError::NotUpgradeAuthority);
+    let result = send(&mut ctx, &[migrate_leaderboard(payer)], &[]).await;
+    assert_error(result, CardGameError::AccountAlreadyMigrated);
+
+    let player = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let result = send(&mut ctx, &[migrate_user_stats(payer, player.pubkey())], &[]).await;
+    assert_error(result, CardGameError::AccountAlreadyMigrated);
+
+    let veteran = Keypair::new().pubkey();
+    let legacy = LegacyUserStats { score: 10, name: "Veteran".to_string(), bump: user_stats_pda(&veteran).1, active_game: None };
+    set_legacy_user_stats(&mut ctx, &veteran, &legacy);
+    send(&mut ctx, &[migrate_user_stats(payer, veteran)], &[]).await.unwrap();
+    let result = send(&mut ctx, &[migrate_user_stats(payer, veteran)], &[]).await;
+    assert_error(result, CardGameError::AccountAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn card_registry_errors() {
+    let mut ctx = start().await;
+    let admin = ctx.payer.pubkey();
+
+    // Nobody but the upgrade authority can claim the registry, even before it exists
+    let intruder = Keypair::new();
+    fund(&mut ctx, &intruder.pubkey()).await;
+    let result = send(&mut ctx, &[init_card_registry(intruder.pubkey())], &[&intruder]).await;
+    assert_error(result, CardGameError::NotUpgradeAuthority);
+
+    set_upgrade_authority(&mut ctx, None);
+    let result = send(&mut ctx, &[init_card_registry(admin)], &[]).await;
+    assert_error(result, CardGameError::NotUpgradeAuthority);
+
+    set_upgrade_authority(&mut ctx, Some(admin));
+    setup_world(&mut ctx).await;
+
+    let result = send(&mut ctx, &[add_card(admin, unit(0, 1, 1))], &[]).await;
+    assert_error(result, CardGameError::InvalidCardDefinition);
+
+    let result = send(&mut ctx, &[add_card(admin, unit(1, 1, 11))], &[]).await;
+    assert_error(result, CardGameError::InvalidCardDefinition);
+
+    let result = send(&mut ctx, &[update_card(admin, 99, unit(1, 1, 1))], &[]).await;
+    assert_error(result, CardGameError::UnknownCardId);
+
+    let result = send(&mut ctx, &[add_card(intruder.pubkey(), unit(1, 1, 1))], &[&intruder]).await;
+    assert_error(result, CardGameError::NotRegistryAdmin);
+
+    let result = send(&mut ctx, &[update_card(intruder.pubkey(), 0, unit(1, 1, 1))], &[&intruder]).await;
+    assert_error(result, CardGameError::NotRegistryAdmin);
+
+    let remaining = CardRegistry::MAX_CARDS - test_cards().len();
+    for start in (0..remaining).step_by(20) {
+        let ixs: Vec<_> = (start..remaining.min(start + 20)).map(|_| add_card(admin, unit(1, 1, 1))).collect();
+        send(&mut ctx, &ixs, &[]).await.unwrap();
+    }
+    let result = send(&mut ctx, &[add_card(admin, unit(1, 1, 1))], &[]).await;
+    assert_error(result, CardGameError::CardRegistryFull);
+}
+
+#[tokio::test]
+async fn challenge_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(FOOTMAN_ID)).await;
+    let p3 = register(&mut ctx, "Player Three", deck_of(FOOTMAN_ID)).await;
+
+    for turn_limit in [MIN_TURN_LIMIT - 1, MAX_TURN_LIMIT + 1] {
+        let game = Keypair::new();
+        let ix = create_challenge(game.pubkey(), p1.pubkey(), p2.pubkey(), 0, turn_limit, commitment_of(&p1.pubkey()));
+        let result = send(&mut ctx, &[ix], &[&p1, &game]).await;
+        assert_error(result, CardGameError::InvalidTurnLimit);
+    }
+
+    let game = challenge(&mut ctx, &p1, &p2).await.pubkey();
+
+    let ix = accept_challenge(game, p1.pubkey(), p3.pubkey(), 0, commitment_of(&p3.pubkey()));
+    let result = send(&mut ctx, &[ix], &[&p3]).await;
+    assert_error(result, CardGameError::MismatchPlayerKeys);
+
+    let other = Keypair::new();
+    let ix = create_challenge(other.pubkey(), p1.pubkey(), p3.pubkey(), 0, TURN_LIMIT, commitment_of(&p1.pubkey()));
+    let result = send(&mut ctx, &[ix], &[&p1, &other]).await;
+    assert_error(result, CardGameError::YouHaveAnActiveGame);
+
+    let ix = create_challenge(other.pubkey(), p3.pubkey(), p1.pubkey(), 0, TURN_LIMIT, commitment_of(&p3.pubkey()));
+    let result = send(&mut ctx, &[ix], &[&p3, &other]).await;
+    assert_error(result, CardGameError::OpponentHasAnActiveGame);
+
+    accept(&mut ctx, &game, &p1, &p2).await;
+
+    let result = send(&mut ctx, &[cancel_challenge(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::ChallengeNotPending);
+
+    let result = send(&mut ctx, &[decline_challenge(game, p1.pubkey(), p2.pubkey())], &[&p2]).await;
+    assert_error(result, CardGameError::ChallengeNotPending);
+}
+
+#[tokio::test]
+async fn seed_reveal_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(FOOTMAN_ID)).await;
+    let p3 = register(&mut ctx, "Player Three", deck_of(FOOTMAN_ID)).await;
+    let game = challenge(&mut ctx, &p1, &p2).await.pubkey();
+    let p1_link = link_of(&p1.pubkey(), 1);
+
+    let result = send(&mut ctx, &[reveal_seed(game, p1.pubkey(), p1_link)], &[&p1]).await;
+    assert_error(result, CardGameError::NotRevealingSeeds);
+
+    accept(&mut ctx, &game, &p1, &p2).await;
+
+    // Nobody is on turn until both seeds are revealed
+    let result = send(&mut ctx, &[play_card(game, p1.pubkey(), 0, 0, None)], &[&p1]).await;
+    assert_error(result, CardGameError::NotPlayersTurn);
+
+    let result = send(&mut ctx, &[reveal_seed(game, p3.pubkey(), link_of(&p3.pubkey(), 1))], &[&p3]).await;
+    assert_error(result, CardGameError::MismatchPlayerKeys);
+
+    // A commitment only opens with its own player's link, and only with the next one down the chain
+    let result = send(&mut ctx, &[reveal_seed(game, p1.pubkey(), link_of(&p2.pubkey(), 1))], &[&p1]).await;
+    assert_error(result, CardGameError::InvalidSeedReveal);
+    let result = send(&mut ctx, &[reveal_seed(game, p1.pubkey(), link_of(&p1.pubkey(), 2))], &[&p1]).await;
+    assert_error(result, CardGameError::InvalidSeedReveal);
+
+    // Neither player revealed yet, so both are holding the game up
+    let result = send(&mut ctx, &[claim_timeout(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::CannotClaimOwnTurn);
+
+    reveal(&mut ctx, &game, &p1).await;
+
+    let result = send(&mut ctx, &[reveal_seed(game, p1.pubkey(), p1_link)], &[&p1]).await;
+    assert_error(result, CardGameError::SeedAlreadyRevealed);
+
+    let result = send(&mut ctx, &[claim_timeout(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::TurnNotTimedOut);
+
+    let result = send(&mut ctx, &[close_game(game, p1.pubkey(), p1.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::GameStillActive);
+
+    reveal(&mut ctx, &game, &p2).await;
+
+    // Mid game the only reveal taken is the next player's, after the player on turn ended it
+    let result = send(&mut ctx, &[reveal_seed(game, p2.pubkey(), link_of(&p2.pubkey(), 2))], &[&p2]).await;
+    assert_error(result, CardGameError::NotRevealingSeeds);
+
+    let result = send(&mut ctx, &[end_turn(game, p1.pubkey(), p1_link)], &[&p1]).await;
+    assert_error(result, CardGameError::InvalidSeedReveal);
+    send(&mut ctx, &[end_turn(game, p1.pubkey(), link_of(&p1.pubkey(), 2))], &[&p1]).await.unwrap();
+
+    let result = send(&mut ctx, &[reveal_seed(game, p1.pubkey(), link_of(&p1.pubkey(), 3))], &[&p1]).await;
+    assert_error(result, CardGameError::NotRevealingSeeds);
+    let result = send(&mut ctx, &[play_card(game, p1.pubkey(), 0, 0, None)], &[&p1]).await;
+    assert_error(result, CardGameError::NotPlayersTurn);
+
+    // Player 2 is up, but nothing of their turn is dealt before they reveal
+    let result = send(&mut ctx, &[play_card(game, p2.pubkey(), 0, 0, None)], &[&p2]).await;
+    assert_error(result, CardGameError::TurnSeedNotRevealed);
+    let result = send(&mut ctx, &[end_turn(game, p2.pubkey(), link_of(&p2.pubkey(), 2))], &[&p2]).await;
+    assert_error(result, CardGameError::TurnSeedNotRevealed);
+    let result = send(&mut ctx, &[claim_timeout(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::TurnNotTimedOut);
+
+    send(&mut ctx, &[reveal_seed(game, p2.pubkey(), link_of(&p2.pubkey(), 2))], &[&p2]).await.unwrap();
+    send(&mut ctx, &[play_card(game, p2.pubkey(), 0, 0, None)], &[&p2]).await.unwrap();
+}
+
+#[tokio::test]
+async fn settlement_errors() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(FOOTMAN_ID)).await;
+    let p3 = register(&mut ctx, "Player Three", deck_of(FOOTMAN_ID)).await;
+    let game = start_game(&mut ctx, &p1, &p2).await.pubkey();
+
+    let result = send(&mut ctx, &[set_scores(game, p1.pubkey(), p2.pubkey())], &[]).await;
+    assert_error(result, CardGameError::GameStillActive);
+
+    let result = send(&mut ctx, &[close_game(game, p1.pubkey(), p1.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::GameStillActive);
+
+    let result = send(&mut ctx, &[claim_timeout(game, p2.pubkey(), p1.pubkey())], &[&p2]).await;
+    assert_error(result, CardGameError::TurnNotTimedOut);
+
+    // Only the player on turn can be timed out
+    let result = send(&mut ctx, &[claim_timeout(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::MismatchPlayerKeys);
+
+    let result = send(&mut ctx, &[concede(game, p1.pubkey(), p3.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::MismatchPlayerKeys);
+
+    send(&mut ctx, &[concede(game, p1.pubkey(), p2.pubkey())], &[&p1]).await.unwrap();
+
+    // Conceding settles the game, nothing can hand out its scores a second time
+    let result = send(&mut ctx, &[set_scores(game, p1.pubkey(), p2.pubkey())], &[]).await;
+    assert_error(result, CardGameError::GameAlreadySettled);
+    let result = send(&mut ctx, &[concede(game, p1.pubkey(), p2.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::GameAlreadySettled);
+    let now = unix_timestamp(&mut ctx).await;
+    set_unix_timestamp(&mut ctx, now + TURN_LIMIT).await;
+    let result = send(&mut ctx, &[claim_timeout(game, p2.pubkey(), p1.pubkey())], &[&p2]).await;
+    assert_error(result, CardGameError::GameAlreadySettled);
+}
+
+#[tokio::test]
+async fn season_errors() {
+    let mut ctx = start().await;
+    let admin = ctx.payer.pubkey();
+
+    let intruder = Keypair::new();
+    fund(&mut ctx, &intruder.pubkey()).await;
+    let result = send(&mut ctx, &[init_season(intruder.pubkey(), SEASON_DURATION)], &[&intruder]).await;
+    assert_error(result, CardGameError::NotUpgradeAuthority);
+
+    let result = send(&mut ctx, &[init_season(admin, 0)], &[]).await;
+    assert_error(result, CardGameError::InvalidSeasonDuration);
+
+    send(&mut ctx, &[init_leaderboard(admin), init_season(admin, SEASON_DURATION)], &[]).await.unwrap();
+    let result = send(&mut ctx, &[rollover_season(admin, 1)], &[]).await;
+    assert_error(result, CardGameError::SeasonNotOver);
+
+    let now = unix_timestamp(&mut ctx).await;
+    set_unix_timestamp(&mut ctx, now + SEASON_DURATION).await;
+    let result = send(&mut ctx, &[rollover_season(intruder.pubkey(), 1)], &[&intruder]).await;
+    assert_error(result, CardGameError::NotSeasonAdmin);
+}
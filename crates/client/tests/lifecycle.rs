@@ -0,0 +1,392 @@
+// Full games played against the program, from registration to settling and closing
+mod common;
+
+use card::errors::CardGameError;
+use card::state::LegacyUserStats;
+use card_client::*;
+use card_engine as engine;
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn game_is_played_to_a_win_and_settled() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(RAIDER_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(FOOTMAN_ID)).await;
+
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    let ranked: Vec<_> = leaderboard.entries.iter().map(|entry| entry.player).collect();
+    assert_eq!(ranked, vec![p1.pubkey(), p2.pubkey()]);
+
+    let game = challenge(&mut ctx, &p1, &p2).await;
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.get_game_state() == GameState::Pending);
+    let stats: UserStats = fetch(&mut ctx, user_stats_pda(&p1.pubkey()).0).await;
+    assert_eq!(stats.check_active_game(), Some(game.pubkey()));
+
+    accept(&mut ctx, &game.pubkey(), &p1, &p2).await;
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.get_game_state() == GameState::Revealing);
+    assert_eq!(state.get_turn(), 0);
+
+    // Either player can reveal first
+    reveal(&mut ctx, &game.pubkey(), &p2).await;
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.is_revealing());
+    reveal(&mut ctx, &game.pubkey(), &p1).await;
+
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.is_active());
+    assert_eq!(state.get_turn(), 1);
+    assert_eq!(state.get_health(), [30, 30]);
+    assert_eq!(state.get_mana(), [1, 1]);
+    assert_eq!(state.get_hand(0).len(), 3);
+    assert_eq!(state.get_hand(1).len(), 3);
+    assert_eq!(state.get_deck(0).len(), 27);
+    assert_eq!(state.get_turn_limit(), TURN_LIMIT);
+
+    // Turn 1: a charging raider goes straight for the enemy hero
+    let game_pk = game.pubkey();
+    let ixs = [
+        play_card(game_pk, p1.pubkey(), 0, 0, None),
+        attack(game_pk, p1.pubkey(), 0, AttackTarget::Hero),
+    ];
+    send(&mut ctx, &ixs, &[&p1]).await.unwrap();
+    let state: Game = fetch(&mut ctx, game_pk).await;
+    assert_eq!(state.get_health(), [30, 20]);
+    assert_eq!(state.get_mana(), [0, 1]);
+    assert_eq!(state.get_board()[1][0].unwrap().moves, 0);
+    pass_turn(&mut ctx, &game_pk, 1, &p1, &p2).await;
+
+    // Turn 2: player 2 draws and plays a footman that can't attack yet
+    let state: Game = fetch(&mut ctx, game_pk).await;
+    assert_eq!(state.get_hand(1).len(), 4);
+    send(&mut ctx, &[play_card(game_pk, p2.pubkey(), 3, 0, None)], &[&p2]).await.unwrap();
+    pass_turn(&mut ctx, &game_pk, 2, &p2, &p1).await;
+
+    // Turn 3: two raiders finish the game
+    let state: Game = fetch(&mut ctx, game_pk).await;
+    assert_eq!(state.get_turn(), 3);
+    assert_eq!(state.get_mana(), [2, 2]);
+    let ixs = [
+        play_card(game_pk, p1.pubkey(), 1, 0, None),
+        attack(game_pk, p1.pubkey(), 0, AttackTarget::Hero),
+        attack(game_pk, p1.pubkey(), 1, AttackTarget::Hero),
+    ];
+    send(&mut ctx, &ixs, &[&p1]).await.unwrap();
+
+    let state: Game = fetch(&mut ctx, game_pk).await;
+    assert_eq!(state.get_health(), [30, 0]);
+    assert!(state.get_game_state() == GameState::Won { winner: p1.pubkey() });
+    assert!(!state.is_settled());
+
+    let result = send(&mut ctx, &[end_turn(game_pk, p1.pubkey(), link_of(&p1.pubkey(), 4))], &[&p1]).await;
+    assert_error(result, CardGameError::GameAlreadyOver);
+    let result = send(&mut ctx, &[close_game(game_pk, p1.pubkey(), p1.pubkey())], &[&p1]).await;
+    assert_error(result, CardGameError::GameNotSettled);
+
+    // Someone outside the game can't be scored against either player
+    let bystander = register(&mut ctx, "Bystander", deck_of(FOOTMAN_ID)).await;
+    let result = send(&mut ctx, &[set_scores(game_pk, p1.pubkey(), bystander.pubkey())], &[]).await;
+    assert_error(result, CardGameError::NotPlayersActiveGame);
+
+    send(&mut ctx, &[set_scores(game_pk, p1.pubkey(), p2.pubkey())], &[]).await.unwrap();
+    let result = send(&mut ctx, &[set_scores(game_pk, p1.pubkey(), p2.pubkey())], &[]).await;
+    assert_error(result, CardGameError::GameAlreadySettled);
+
+    let state: Game = fetch(&mut ctx, game_pk).await;
+    assert!(state.is_settled());
+    let p1_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p1.pubkey()).0).await;
+    let p2_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p2.pubkey()).0).await;
+    assert_eq!(p1_stats.get_score(), 1232);
+    assert_eq!(p2_stats.get_score(), 1168);
+    assert_eq!(p1_stats.get_games_played(), 1);
+    assert_eq!(p1_stats.check_active_game(), None);
+    assert_eq!(p2_stats.check_active_game(), None);
+
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    let ranking: Vec<_> = leaderboard.entries.iter().map(|entry| (entry.player, entry.score)).collect();
+    assert_eq!(ranking, vec![(p1.pubkey(), 1232), (bystander.pubkey(), 1200), (p2.pubkey(), 1168)]);
+
+    // Rent goes back to player 1 even when player 2 closes the game
+    let balance = lamports(&mut ctx, p1.pubkey()).await;
+    let rent = lamports(&mut ctx, game_pk).await;
+    send(&mut ctx, &[close_game(game_pk, p1.pubkey(), p2.pubkey())], &[&p2]).await.unwrap();
+    assert!(!account_exists(&mut ctx, game_pk).await);
+    assert_eq!(lamports(&mut ctx, p1.pubkey()).await, balance + rent);
+}
+
+#[tokio::test]
+async fn revealed_seeds_decide_the_opening_hands() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let deck: Vec<u16> = (0..30).map(|i| i % test_cards().len() as u16).collect();
+    let p1 = register(&mut ctx, "Player One", deck.clone()).await;
+    let p2 = register(&mut ctx, "Player Two", deck.clone()).await;
+    let game = start_game(&mut ctx, &p1, &p2).await;
+
+    // Both links are used up, the next ones have to hash to them
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    let links = [link_of(&p1.pubkey(), 1), link_of(&p2.pubkey(), 1)];
+    assert_eq!(state.get_reveals(), [None, None]);
+    assert_eq!(state.get_commitments(), links);
+
+    // Anyone can replay the draws from the two reveals
+    let cards = test_cards();
+    let engine_deck: Vec<engine::Card> = deck.iter().map(|id| cards[*id as usize].into()).collect();
+    let expected = engine::Game::new([engine_deck.clone(), engine_deck], combine_seeds(0, 0, &links[0], &links[1]));
+    for player in 0..2 {
+        let hand: Vec<engine::Card> = state.get_hand(player).iter().map(|card| (*card).into()).collect();
+        let deck: Vec<engine::Card> = state.get_deck(player).iter().map(|card| (*card).into()).collect();
+        assert_eq!(hand, expected.hands[player]);
+        assert_eq!(deck, expected.decks[player]);
+    }
+}
+
+// The engine's view of a game account, as anyone reading the account could build it
+fn engine_state(state: &Game) -> engine::Game {
+    let cards = |cards: &[Card]| cards.iter().map(|card| (*card).into()).collect::<Vec<engine::Card>>();
+    engine::Game {
+        turn: state.get_turn(),
+        board: state.get_board().map(|row| row.map(|tile| tile.map(Into::into))),
+        state: engine::GameState::Active,
+        health: state.get_health(),
+        mana: state.get_mana(),
+        hands: [cards(state.get_hand(0)), cards(state.get_hand(1))],
+        decks: [cards(state.get_deck(0)), cards(state.get_deck(1))],
+        seed: state.get_seed(),
+    }
+}
+
+#[tokio::test]
+async fn next_draw_is_only_fixed_once_both_links_are_revealed() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let deck: Vec<u16> = (0..30).map(|i| i % test_cards().len() as u16).collect();
+    let p1 = register(&mut ctx, "Player One", deck.clone()).await;
+    let p2 = register(&mut ctx, "Player Two", deck).await;
+    let game = start_game(&mut ctx, &p1, &p2).await.pubkey();
+
+    // Ending the turn only hands over player 1's link, player 2 hasn't drawn yet
+    let p1_link = link_of(&p1.pubkey(), 2);
+    send(&mut ctx, &[end_turn(game, p1.pubkey(), p1_link)], &[&p1]).await.unwrap();
+    let ended: Game = fetch(&mut ctx, game).await;
+    assert_eq!(ended.get_turn(), 1);
+    assert_eq!(ended.get_reveals(), [Some(p1_link), None]);
+    assert_eq!(ended.get_hand(1).len(), 3);
+
+    // Dealing the turn from everything the account holds at this point isn't what gets dealt
+    let mut read_ahead = engine_state(&ended);
+    read_ahead.end_turn().unwrap();
+
+    let p2_link = link_of(&p2.pubkey(), 2);
+    send(&mut ctx, &[reveal_seed(game, p2.pubkey(), p2_link)], &[&p2]).await.unwrap();
+    let dealt: Game = fetch(&mut ctx, game).await;
+    assert_eq!(dealt.get_turn(), 2);
+    assert_eq!(dealt.get_hand(1).len(), 4);
+    assert_ne!(dealt.get_seed(), read_ahead.seed);
+
+    // It takes player 2's link, which only they knew until now
+    let mut expected = engine_state(&ended);
+    expected.seed = combine_seeds(ended.get_seed(), 1, &p1_link, &p2_link);
+    expected.end_turn().unwrap();
+    assert_eq!(engine_state(&dealt), expected);
+}
+
+#[tokio::test]
+async fn player_who_wont_reveal_loses_on_timeout() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(SHIELDBEARER_ID)).await;
+    let game = challenge(&mut ctx, &p1, &p2).await.pubkey();
+    accept(&mut ctx, &game, &p1, &p2).await;
+    reveal(&mut ctx, &game, &p1).await;
+
+    let state: Game = fetch(&mut ctx, game).await;
+    set_unix_timestamp(&mut ctx, state.get_turn_started_at() + TURN_LIMIT).await;
+    send(&mut ctx, &[claim_timeout(game, p1.pubkey(), p2.pubkey())], &[&p1]).await.unwrap();
+
+    let state: Game = fetch(&mut ctx, game).await;
+    assert!(state.get_game_state() == GameState::Won { winner: p1.pubkey() });
+    assert!(state.is_settled());
+    let p2_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p2.pubkey()).0).await;
+    assert_eq!(p2_stats.get_score(), 1168);
+    assert_eq!(p2_stats.check_active_game(), None);
+}
+
+#[tokio::test]
+async fn conceding_hands_the_win_to_the_opponent() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(SHIELDBEARER_ID)).await;
+    let game = start_game(&mut ctx, &p1, &p2).await;
+
+    // Player 2 can concede even though it is player 1's turn
+    send(&mut ctx, &[concede(game.pubkey(), p2.pubkey(), p1.pubkey())], &[&p2]).await.unwrap();
+
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.get_game_state() == GameState::Won { winner: p1.pubkey() });
+    assert!(state.is_settled());
+    let p1_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p1.pubkey()).0).await;
+    let p2_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p2.pubkey()).0).await;
+    assert_eq!(p1_stats.get_score(), 1232);
+    assert_eq!(p2_stats.get_score(), 1168);
+    assert_eq!(p2_stats.check_active_game(), None);
+
+    // Both players are free to start another game
+    start_game(&mut ctx, &p2, &p1).await;
+}
+
+#[tokio::test]
+async fn waiting_player_claims_an_abandoned_game() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(SHIELDBEARER_ID)).await;
+    let game = start_game(&mut ctx, &p1, &p2).await;
+
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    set_unix_timestamp(&mut ctx, state.get_turn_started_at() + TURN_LIMIT).await;
+    send(&mut ctx, &[claim_timeout(game.pubkey(), p2.pubkey(), p1.pubkey())], &[&p2]).await.unwrap();
+
+    let state: Game = fetch(&mut ctx, game.pubkey()).await;
+    assert!(state.get_game_state() == GameState::Won { winner: p2.pubkey() });
+    assert!(state.is_settled());
+    let p2_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p2.pubkey()).0).await;
+    assert_eq!(p2_stats.get_score(), 1232);
+}
+
+#[tokio::test]
+async fn season_rollover_archives_standings_and_soft_resets_ratings() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let p1 = register(&mut ctx, "Player One", deck_of(FOOTMAN_ID)).await;
+    let p2 = register(&mut ctx, "Player Two", deck_of(SHIELDBEARER_ID)).await;
+    let game = start_game(&mut ctx, &p1, &p2).await;
+    send(&mut ctx, &[concede(game.pubkey(), p2.pubkey(), p1.pubkey())], &[&p2]).await.unwrap();
+
+    let season: Season = fetch(&mut ctx, season_pda().0).await;
+    set_unix_timestamp(&mut ctx, season.end_ts).await;
+    let admin = ctx.payer.pubkey();
+    send(&mut ctx, &[rollover_season(admin, season.number)], &[]).await.unwrap();
+
+    let archive: SeasonArchive = fetch(&mut ctx, season_archive_pda(season.number).0).await;
+    assert_eq!(archive.number, 1);
+    let standings: Vec<_> = archive.standings.iter().map(|entry| (entry.player, entry.score)).collect();
+    assert_eq!(standings, vec![(p1.pubkey(), 1232), (p2.pubkey(), 1168)]);
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    assert!(leaderboard.entries.is_empty());
+    let season: Season = fetch(&mut ctx, season_pda().0).await;
+    assert_eq!(season.number, 2);
+
+    // Ratings are pulled halfway back to 1200 before the first game of the new season counts
+    let game = start_game(&mut ctx, &p1, &p2).await;
+    send(&mut ctx, &[concede(game.pubkey(), p1.pubkey(), p2.pubkey())], &[&p1]).await.unwrap();
+    let p1_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p1.pubkey()).0).await;
+    let p2_stats: UserStats = fetch(&mut ctx, user_stats_pda(&p2.pubkey()).0).await;
+    assert_eq!(p1_stats.get_season(), 2);
+    assert_eq!(p1_stats.get_score(), 1216 - 35);
+    assert_eq!(p2_stats.get_score(), 1184 + 35);
+}
+
+#[tokio::test]
+async fn leaderboard_keeps_its_size_as_players_register() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+
+    for i in 0..Leaderboard::MAX_ENTRIES + 5 {
+        register(&mut ctx, &format!("Player {}", i), deck_of(FOOTMAN_ID)).await;
+    }
+
+    let account = ctx.banks_client.get_account(leaderboard_pda().0).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + Leaderboard::MAX_ACC_SIZE);
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    assert_eq!(leaderboard.entries.len(), Leaderboard::MAX_ENTRIES);
+}
+
+#[tokio::test]
+async fn legacy_user_stats_are_migrated_to_ratings() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+
+    // A long name and an active game no longer fit the old allocation once ratings are added
+    let veteran = Keypair::new();
+    fund(&mut ctx, &veteran.pubkey()).await;
+    let legacy = LegacyUserStats {
+        score: 45,
+        name: "Veteran of many games".chars().take(20).collect(),
+        bump: user_stats_pda(&veteran.pubkey()).1,
+        active_game: Some(Keypair::new().pubkey()),
+    };
+    set_legacy_user_stats(&mut ctx, &veteran.pubkey(), &legacy);
+
+    let payer = ctx.payer.pubkey();
+    send(&mut ctx, &[migrate_user_stats(payer, veteran.pubkey())], &[]).await.unwrap();
+
+    let account = ctx.banks_client.get_account(user_stats_pda(&veteran.pubkey()).0).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + UserStats::MAX_ACC_SIZE);
+    let stats: UserStats = fetch(&mut ctx, user_stats_pda(&veteran.pubkey()).0).await;
+    assert_eq!(stats.get_name(), legacy.name);
+    assert_eq!(stats.get_score(), 1200);
+    assert_eq!(stats.get_games_played(), 0);
+    assert_eq!(stats.check_active_game(), None);
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    let ranking: Vec<_> = leaderboard.entries.iter().map(|entry| (entry.player, entry.score)).collect();
+    assert_eq!(ranking, vec![(veteran.pubkey(), 1200)]);
+
+    // From here on the account plays like any other
+    send(&mut ctx, &[create_deck(veteran.pubkey(), 0, deck_of(FOOTMAN_ID))], &[&veteran]).await.unwrap();
+    let rookie = register(&mut ctx, "Rookie", deck_of(FOOTMAN_ID)).await;
+    let game = start_game(&mut ctx, &veteran, &rookie).await;
+    send(&mut ctx, &[concede(game.pubkey(), rookie.pubkey(), veteran.pubkey())], &[&rookie]).await.unwrap();
+    let stats: UserStats = fetch(&mut ctx, user_stats_pda(&veteran.pubkey()).0).await;
+    assert_eq!(stats.get_score(), 1232);
+}
+
+#[tokio::test]
+async fn legacy_leaderboard_is_migrated_to_the_top_50() {
+    let mut ctx = start().await;
+    setup_world(&mut ctx).await;
+    let admin = ctx.payer.pubkey();
+
+    // Every player the old leaderboard listed still has user stats in the old layout as well
+    let veterans: Vec<_> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+    set_legacy_leaderboard(&mut ctx, veterans.clone());
+    for (i, veteran) in veterans.iter().enumerate() {
+        let legacy = LegacyUserStats {
+            score: 5 * i as u16,
+            name: format!("Veteran {}", i),
+            bump: user_stats_pda(veteran).1,
+            active_game: None,
+        };
+        set_legacy_user_stats(&mut ctx, veteran, &legacy);
+    }
+
+    // Nothing that loads the leaderboard works until it is migrated
+    let result = send(&mut ctx, &[migrate_user_stats(admin, veterans[0])], &[]).await;
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+
+    let before = lamports(&mut ctx, leaderboard_pda().0).await;
+    send(&mut ctx, &[migrate_leaderboard(admin)], &[]).await.unwrap();
+    let account = ctx.banks_client.get_account(leaderboard_pda().0).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + Leaderboard::MAX_ACC_SIZE);
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.lamports, rent.minimum_balance(account.data.len()));
+    assert!(account.lamports < before);
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    assert_eq!(leaderboard.bump, leaderboard_pda().1);
+    assert!(leaderboard.entries.is_empty());
+
+    let ixs: Vec<_> = veterans.iter().map(|veteran| migrate_user_stats(admin, *veteran)).collect();
+    send(&mut ctx, &ixs, &[]).await.unwrap();
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    let ranked: Vec<_> = leaderboard.entries.iter().map(|entry| entry.player).collect();
+    assert_eq!(ranked, veterans);
+
+    let newcomer = register(&mut ctx, "Newcomer", deck_of(FOOTMAN_ID)).await;
+    let leaderboard: Leaderboard = fetch(&mut ctx, leaderboard_pda().0).await;
+    assert_eq!(leaderboard.entries.last().unwrap().player, newcomer.pubkey());
+}
@@ -0,0 +1,141 @@
+// Spells resolve their effect straight away and never occupy a board tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardKind {
+    Unit,
+    Spell { effect: Effect },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Damage { amount: i8 },
+    Heal { amount: i8 },
+    Buff { atk: i8, hp: i8 },
+    HealOwnHero { amount: i8 },
+    BuffAdjacent { atk: i8, hp: i8 },
+    Summon { hp: i8, atk: i8 },
+    DamageEnemyHero { amount: i8 },
+    DamageRandomEnemy { amount: i8 },
+    DrawCard,
+}
+
+impl Effect {
+    // Whether the player has to pick a unit or hero for the effect to land on
+    pub fn needs_target(&self) -> bool {
+        matches!(self, Effect::Damage { .. } | Effect::Heal { .. } | Effect::Buff { .. })
+    }
+}
+
+// Ongoing bonus given to allied units on the same row while the aura unit is alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aura {
+    Adjacent { atk: i8, hp: i8 },
+    OtherAllies { atk: i8, hp: i8 },
+}
+
+impl Aura {
+    pub fn applies_to(&self, source: usize, pos: usize) -> bool {
+        match self {
+            Aura::Adjacent { .. } => source.abs_diff(pos) == 1,
+            Aura::OtherAllies { .. } => source != pos,
+        }
+    }
+
+    pub fn stats(&self) -> (i8, i8) {
+        match *self {
+            Aura::Adjacent { atk, hp } | Aura::OtherAllies { atk, hp } => (atk, hp),
+        }
+    }
+}
+
+// Board coordinates of an effect target, pos = HERO_POS targets the hero behind the row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectTarget {
+    pub row: u8,
+    pub pos: u8,
+}
+
+// Charge units can attack the turn they are played, rush units can only attack other units on that turn
+// hp and atk are the unit's own stats, aura_hp and aura_atk are recomputed from the board by Game
+// max_hp is what heals top hp up to, set when the unit enters the board and raised by buffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub hp: i8,
+    pub max_hp: i8,
+    pub atk: i8,
+    pub mana: i8,
+    pub moves: i8,
+    pub taunt: bool,
+    pub charge: bool,
+    pub rush: bool,
+    pub summoning_sick: bool,
+    pub divine_shield: bool,
+    pub kind: CardKind,
+    pub battlecry: Option<Effect>,
+    pub deathrattle: Option<Effect>,
+    pub aura: Option<Aura>,
+    pub aura_atk: i8,
+    pub aura_hp: i8,
+}
+
+impl Card {
+    pub fn effective_atk(&self) -> i8 {
+        self.atk.saturating_add(self.aura_atk)
+    }
+
+    pub fn effective_hp(&self) -> i8 {
+        self.hp.saturating_add(self.aura_hp)
+    }
+
+    // Plain unit summoned by an effect
+    pub fn token(hp: i8, atk: i8, ready: bool) -> Card {
+        Card {
+            hp,
+            max_hp: hp,
+            atk,
+            mana: 0,
+            moves: if ready { 1 } else { 0 },
+            taunt: false,
+            charge: false,
+            rush: false,
+            summoning_sick: !ready,
+            divine_shield: false,
+            kind: CardKind::Unit,
+            battlecry: None,
+            deathrattle: None,
+            aura: None,
+            aura_atk: 0,
+            aura_hp: 0,
+        }
+    }
+
+    pub fn is_spell(&self) -> bool {
+        matches!(self.kind, CardKind::Spell { .. })
+    }
+
+    // Heals never take a unit past its max hp, nor lower hp that is already above it
+    pub fn heal(&mut self, amount: i8) {
+        if self.hp < self.max_hp {
+            self.hp = self.hp.saturating_add(amount).min(self.max_hp);
+        }
+    }
+
+    // Buffs move max hp along with hp, so a buffed unit can be healed back up to its new total
+    pub fn buff(&mut self, atk: i8, hp: i8) {
+        self.atk = self.atk.saturating_add(atk);
+        self.hp = self.hp.saturating_add(hp);
+        self.max_hp = self.max_hp.saturating_add(hp);
+    }
+
+    // Every source of unit damage goes through here so divine shield is respected
+    pub fn take_damage(&mut self, amount: i8) {
+        if amount <= 0 {
+            return;
+        }
+
+        if self.divine_shield {
+            self.divine_shield = false;
+        } else {
+            self.hp = self.hp.saturating_sub(amount);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use core::fmt;
+
+// Rule violations, the program maps each onto its error code of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineError {
+    TileOutOfBounds,
+    PositionOutOfBounds,
+    EmptyBoardSpace,
+    TileAlreadySet,
+    GameAlreadyOver,
+    UnitIsNotReady,
+    InsufficientMana,
+    CardIndexOutOfBounds,
+    MustAttackTauntUnit,
+    SpellCannotBePlaced,
+    CardIsNotASpell,
+    InvalidEffectTarget,
+    MissingEffectTarget,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, EngineError>;
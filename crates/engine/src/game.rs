@@ -0,0 +1,583 @@
+use alloc::vec::Vec;
+use crate::card::*;
+use crate::error::{EngineError, Result};
+
+// Max index of cards on each row
+pub const MAX_ROW: u8 = 6;
+
+// Position used to target a hero instead of a unit
+pub const HERO_POS: u8 = MAX_ROW + 1;
+
+pub const STARTING_HEALTH: i8 = 30;
+
+// Cards drawn by each player before the first turn
+pub const STARTING_HAND: usize = 3;
+
+// Cards drawn into a full hand are discarded
+pub const MAX_HAND: usize = 10;
+
+// Mana a player's pool refills to stops growing here
+pub const MAX_MANA: i8 = 10;
+
+// Game ends in a tie once the turn counter runs out
+pub const MAX_TURN: u8 = u8::MAX;
+
+// Players are referred to by index, 0 for player 1 and 1 for player 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    Active,
+    Tie,
+    Won { winner: usize },
+}
+
+// What an attacking unit goes for, unit positions are on the opponent's row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackTarget {
+    Unit { pos: u8 },
+    Hero,
+}
+
+// Row 0 is the top (player 2) row and row 1 the bottom (player 1) row
+// health, mana, hands and decks are indexed by player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub turn: u8,
+    pub board: [[Option<Card>; 7]; 2],
+    pub state: GameState,
+    pub health: [i8; 2],
+    pub mana: [i8; 2],
+    pub hands: [Vec<Card>; 2],
+    pub decks: [Vec<Card>; 2],
+    pub seed: u64,
+}
+
+impl Game {
+    // Starts a game on turn 1 with both players drawing their opening hand
+    pub fn new(decks: [Vec<Card>; 2], seed: u64) -> Game {
+        let mut game = Game {
+            turn: 1,
+            board: [[None; 7]; 2],
+            state: GameState::Active,
+            health: [STARTING_HEALTH, STARTING_HEALTH],
+            mana: [1, 1],
+            hands: [Vec::new(), Vec::new()],
+            decks,
+            // xorshift never leaves zero
+            seed: if seed == 0 { 1 } else { seed },
+        };
+        for _ in 0..STARTING_HAND {
+            game.draw_card(0);
+            game.draw_card(1);
+        }
+        game
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == GameState::Active
+    }
+
+    // Player whose turn it is on the given turn number
+    pub fn player_on_turn(turn: u8) -> usize {
+        ((turn - 1) % 2) as usize
+    }
+
+    pub fn current_player(&self) -> usize {
+        Self::player_on_turn(self.turn)
+    }
+
+    // Player 1 has the bottom row; player 2 the top row
+    pub fn current_player_row(&self) -> usize {
+        (self.current_player() + 1) % 2
+    }
+
+    fn row_has_taunt(&self, row: usize) -> bool {
+        self.board[row].iter().flatten().any(|unit| unit.taunt)
+    }
+
+    // Index into health of the hero sitting behind a row
+    fn row_hero(row: usize) -> usize {
+        (row + 1) % 2
+    }
+
+    // xorshift64 behind every draw and random target, the program reseeds it every turn from both players' reveals
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.seed;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.seed = x;
+        x
+    }
+
+    // Moves a random card from the player's deck into their hand
+    fn draw_card(&mut self, player: usize) {
+        let deck_len = self.decks[player].len();
+        if deck_len == 0 {
+            return;
+        }
+
+        let index = (self.next_random() % deck_len as u64) as usize;
+        let card = self.decks[player].swap_remove(index);
+        if self.hands[player].len() < MAX_HAND {
+            self.hands[player].push(card);
+        }
+    }
+
+    // Plays card from player hand to specific board position on player's row
+    // then resolves its battlecry, if any, on the optional target
+    pub fn play_card(&mut self, pos: u8, card_index: u8, target: Option<EffectTarget>) -> Result<()> {
+        if !self.is_active() {
+            return Err(EngineError::GameAlreadyOver)
+        }
+
+        if pos <= MAX_ROW {
+
+            let player = self.current_player();
+            let row = self.current_player_row();
+            let pos = pos as usize;
+            let card_index = card_index as usize;
+
+            match self.board[row][pos] {
+
+                Some(_) => return Err(EngineError::TileAlreadySet),
+
+                None => {
+                    let hand = &self.hands[player];
+                    if hand.len() <= card_index {
+                        return Err(EngineError::CardIndexOutOfBounds)
+                    }
+
+                    let mut card = hand[card_index];
+                    if card.is_spell() {
+                        return Err(EngineError::SpellCannotBePlaced)
+                    }
+                    if card.mana > self.mana[player] {
+                        return Err(EngineError::InsufficientMana)
+                    }
+
+                    // Battlecry target has to be valid before the card leaves the hand
+                    let battlecry = match card.battlecry {
+                        Some(effect) => {
+                            let (target_row, target_pos) = Self::resolve_target(effect, target, row, pos)?;
+                            // The unit being played is a valid target even though its tile is still empty
+                            if (target_row, target_pos) != (row, pos) {
+                                self.check_target(effect, target_row, target_pos)?;
+                            }
+                            Some((effect, target_row, target_pos))
+                        }
+                        None => None,
+                    };
+
+                    self.hands[player].remove(card_index);
+                    self.mana[player] -= card.mana;
+
+                    // Readiness comes from keywords, not from what the client sent
+                    card.moves = if card.charge || card.rush { 1 } else { 0 };
+                    card.summoning_sick = !card.charge;
+                    card.max_hp = card.hp;
+                    self.board[row][pos] = Some(card);
+
+                    if let Some((effect, target_row, target_pos)) = battlecry {
+                        self.apply_effect(effect, target_row, target_pos);
+                    }
+                    self.update_board();
+                }
+            }
+        } else {
+            return Err(EngineError::TileOutOfBounds)
+        }
+
+        self.update_state();
+
+
+        Ok(())
+    }
+
+
+    // Casts spell from player hand onto a unit, or onto a hero with pos = HERO_POS
+    pub fn cast_spell(&mut self, card_index: u8, row: u8, pos: u8) -> Result<()> {
+        if !self.is_active() {
+            return Err(EngineError::GameAlreadyOver)
+        }
+
+        Self::check_bounds(row, pos)?;
+
+        let player = self.current_player();
+        let card_index = card_index as usize;
+        let hand = &self.hands[player];
+        if hand.len() <= card_index {
+            return Err(EngineError::CardIndexOutOfBounds)
+        }
+
+        let card = hand[card_index];
+        let effect = match card.kind {
+            CardKind::Spell { effect } => effect,
+            CardKind::Unit => return Err(EngineError::CardIsNotASpell),
+        };
+        if card.mana > self.mana[player] {
+            return Err(EngineError::InsufficientMana)
+        }
+
+        // Untargeted spells resolve from the caster's side of the board
+        let row = if effect.needs_target() { row as usize } else { self.current_player_row() };
+        let pos = pos as usize;
+        self.check_target(effect, row, pos)?;
+
+        self.hands[player].remove(card_index);
+        self.mana[player] -= card.mana;
+        self.apply_effect(effect, row, pos);
+
+        self.update_board();
+        self.update_state();
+        Ok(())
+    }
+
+    fn check_bounds(row: u8, pos: u8) -> Result<()> {
+        if row > 1 {
+            return Err(EngineError::TileOutOfBounds)
+        }
+        if pos > HERO_POS {
+            return Err(EngineError::PositionOutOfBounds)
+        }
+        Ok(())
+    }
+
+    // Picks the tile a battlecry resolves on; untargeted effects are centred on the played unit
+    fn resolve_target(effect: Effect, target: Option<EffectTarget>, row: usize, pos: usize) -> Result<(usize, usize)> {
+        if !effect.needs_target() {
+            return Ok((row, pos))
+        }
+
+        match target {
+            Some(EffectTarget { row, pos }) => {
+                Self::check_bounds(row, pos)?;
+                Ok((row as usize, pos as usize))
+            }
+            None => Err(EngineError::MissingEffectTarget),
+        }
+    }
+
+    // Makes sure an effect can resolve on the target before anything is spent
+    fn check_target(&self, effect: Effect, row: usize, pos: usize) -> Result<()> {
+        if !effect.needs_target() {
+            return Ok(())
+        }
+
+        if pos == HERO_POS as usize {
+            if let Effect::Buff { .. } = effect {
+                return Err(EngineError::InvalidEffectTarget)
+            }
+        } else if self.board[row][pos].is_none() {
+            return Err(EngineError::EmptyBoardSpace)
+        }
+        Ok(())
+    }
+
+    // Resolves an effect on a target already validated by check_target
+    // Untargeted effects treat (row, pos) as the tile of the card that caused them
+    fn apply_effect(&mut self, effect: Effect, row: usize, pos: usize) {
+        let enemy_row = (row + 1) % 2;
+
+        match effect {
+            Effect::HealOwnHero { amount } => self.heal_hero(Self::row_hero(row), amount),
+
+            Effect::DrawCard => self.draw_card(Self::row_hero(row)),
+
+            Effect::DamageEnemyHero { amount } => self.damage_hero(Self::row_hero(enemy_row), amount),
+
+            Effect::DamageRandomEnemy { amount } => {
+                // Enemy hero is always a candidate, so there is at least one
+                let mut candidates = [HERO_POS as usize; (MAX_ROW + 2) as usize];
+                let mut count = 1;
+                for (i, tile) in self.board[enemy_row].iter().enumerate() {
+                    if tile.is_some() {
+                        candidates[count] = i;
+                        count += 1;
+                    }
+                }
+                let choice = candidates[(self.next_random() % count as u64) as usize];
+                self.apply_effect(Effect::Damage { amount }, enemy_row, choice);
+            }
+
+            Effect::BuffAdjacent { atk, hp } => {
+                for adjacent in [pos.wrapping_sub(1), pos + 1] {
+                    if adjacent > MAX_ROW as usize {
+                        continue;
+                    }
+                    if let Some(unit) = &mut self.board[row][adjacent] {
+                        unit.buff(atk, hp);
+                    }
+                }
+            }
+
+            // Tokens only land on a free tile
+            Effect::Summon { hp, atk } => {
+                if pos <= MAX_ROW as usize && self.board[row][pos].is_none() {
+                    // A token showing up on the opponent's turn is ready for its owner's next turn
+                    let ready = row != self.current_player_row();
+                    self.board[row][pos] = Some(Card::token(hp, atk, ready));
+                }
+            }
+
+            Effect::Damage { amount } => {
+                if pos == HERO_POS as usize {
+                    self.damage_hero(Self::row_hero(row), amount);
+                } else if let Some(unit) = &mut self.board[row][pos] {
+                    unit.take_damage(amount);
+                }
+            }
+
+            Effect::Heal { amount } => {
+                if pos == HERO_POS as usize {
+                    self.heal_hero(Self::row_hero(row), amount);
+                } else if let Some(unit) = &mut self.board[row][pos] {
+                    unit.heal(amount);
+                }
+            }
+
+            Effect::Buff { atk, hp } => {
+                if let Some(unit) = &mut self.board[row][pos] {
+                    unit.buff(atk, hp);
+                }
+            }
+        }
+    }
+
+    fn damage_hero(&mut self, hero: usize, amount: i8) {
+        self.health[hero] = self.health[hero].saturating_sub(amount);
+    }
+
+    fn heal_hero(&mut self, hero: usize, amount: i8) {
+        self.health[hero] = self.health[hero].saturating_add(amount).min(STARTING_HEALTH);
+    }
+
+
+    // Check if either hero is 0hp or less or if game can continue
+    // Deathrattles can take both heroes down in the same action, which is a tie
+    fn update_state(& mut self) {
+        
+        if self.health[0] <= 0 && self.health[1] <= 0 {
+            self.state = GameState::Tie;
+            return;
+        } else if self.health[0] <= 0 {
+            self.state = GameState::Won { winner: 1 };
+            return;
+        } else if self.health[1] <= 0 {
+            self.state = GameState::Won { winner: 0 };
+            return;
+        }
+
+
+        // Tie game if no player has cards left and heros are still alive
+        for arr in self.board {
+            for cell in arr {
+                if cell.is_some() {
+                    return;
+                }
+            }
+        }
+        if self.hands.iter().all(Vec::is_empty) && self.decks.iter().all(Vec::is_empty) {
+            self.state = GameState::Tie;
+        }
+
+    }
+
+
+    // Finishes current player's turn and iterates turn 
+    pub fn end_turn(& mut self) -> Result<()> {
+        if !self.is_active() {
+            return Err(EngineError::GameAlreadyOver)
+        }
+
+        // Reset unit moves, so they can move next turn
+        for mut unit in &mut self.board[self.current_player_row()] {
+            if let Some(card) = &mut unit {
+                card.moves = 1;
+                card.summoning_sick = false;
+            }
+        }
+        if self.mana[self.current_player()] < MAX_MANA {
+            self.mana[self.current_player()] = ((self.turn - 1) / 2 + 2).min(MAX_MANA as u8) as i8;
+        }
+        if self.turn == MAX_TURN {
+            self.state = GameState::Tie;
+            return Ok(())
+        }
+        self.turn += 1;
+        self.draw_card(self.current_player());
+        self.recompute_auras();
+        Ok(())
+    }
+
+    
+    // Attacks with the unit at ally_pos on the current player's row
+    // Positions past the last tile are rejected whether they point at the ally or the enemy row
+    pub fn attack(&mut self, ally_pos: u8, target: AttackTarget) -> Result<()> {
+        if !self.is_active() {
+            return Err(EngineError::GameAlreadyOver)
+        }
+
+        if ally_pos > MAX_ROW {
+            return Err(EngineError::PositionOutOfBounds)
+        }
+
+        match target {
+            AttackTarget::Unit { pos } if pos <= MAX_ROW => {
+                // attack_unit works on board rows, player 1's units are on the bottom one
+                let (bot_pos, top_pos) = if self.current_player_row() == 1 { (ally_pos, pos) } else { (pos, ally_pos) };
+                self.attack_unit(bot_pos as usize, top_pos as usize)
+            }
+
+            AttackTarget::Hero =>
+                self.attack_hero(ally_pos as usize),
+
+            AttackTarget::Unit { .. } =>
+                Err(EngineError::PositionOutOfBounds)
+        }
+    }
+    
+
+    // Deducts hp from the attacking and attacked unit
+    fn attack_unit(&mut self, bot_pos: usize, top_pos: usize) -> Result<()> {
+
+        let user_row = self.current_player_row();
+
+        // Enemy taunt units have to be dealt with before any other unit
+        let (enemy_row, enemy_pos) = if user_row == 1 { (0, top_pos) } else { (1, bot_pos) };
+        if let Some(target) = self.board[enemy_row][enemy_pos] {
+            if !target.taunt && self.row_has_taunt(enemy_row) {
+                return Err(EngineError::MustAttackTauntUnit)
+            }
+        }
+
+        // Use split_at_mut to borrow two mutable references
+        // One into bottom row, other into top row
+        let (top_row, bottom_row)
+            = self.board.split_at_mut(1);
+
+
+        if let (Some(bot_unit), Some(top_unit))
+            = (&mut bottom_row[0][bot_pos], &mut top_row[0][top_pos]) {
+                if user_row == 1 {
+                    if bot_unit.moves == 0 {
+                        return Err(EngineError::UnitIsNotReady)
+                    } else {
+                        bot_unit.moves = 0;
+                    }
+                } else {
+                    if top_unit.moves == 0 {
+                        return Err(EngineError::UnitIsNotReady)
+                    } else {
+                        top_unit.moves = 0;
+                    }
+                }
+
+                let (bot_atk, top_atk) = (bot_unit.effective_atk(), top_unit.effective_atk());
+                bot_unit.take_damage(top_atk);
+                top_unit.take_damage(bot_atk);
+
+                self.update_board();
+        } else {
+            return Err(EngineError::EmptyBoardSpace)
+        }
+
+        self.update_state();
+        Ok(())
+    }
+
+    // Deducts hp from the enemy hero
+    fn attack_hero(&mut self, ally_pos: usize) -> Result<()> {
+        // No update board run in this func since no unit can die
+        let row = self.current_player_row();
+        let enemy_row = (row + 1) % 2;
+
+        let taunted = self.row_has_taunt(enemy_row);
+        let atk = if let Some(unit) = &mut self.board[row][ally_pos] {
+            if taunted {
+                return Err(EngineError::MustAttackTauntUnit)
+            }
+            // Rush units have to wait a turn before going face
+            if unit.moves == 0 || unit.summoning_sick {
+                return Err(EngineError::UnitIsNotReady)
+            } else {
+                unit.moves = 0;
+            }
+            unit.effective_atk()
+        } else {
+            return Err(EngineError::EmptyBoardSpace)
+        };
+        self.damage_hero(Self::row_hero(enemy_row), atk);
+
+        self.update_state();
+        Ok(())
+    }
+
+    // Clear out units with 0 or less hp and fire their deathrattles
+    // Deaths are resolved attacker side first, left to right, until no unit is left at 0 hp
+    fn update_board(&mut self) {
+        let attacker_row = self.current_player_row();
+        let rows = [attacker_row, (attacker_row + 1) % 2];
+
+        loop {
+            // Auras lost to the previous round of deaths no longer count
+            self.recompute_auras();
+
+            let mut dead = Vec::new();
+            for i in rows {
+
+                for j in 0..=MAX_ROW as usize {
+
+                    if let Some(unit) = self.board[i][j] {
+                        if unit.effective_hp() <= 0 {
+                            self.board[i][j] = None;
+                            dead.push((unit, i, j));
+                        }
+                    } 
+                }
+            }
+
+            if dead.is_empty() {
+                break;
+            }
+
+            for (unit, i, j) in dead {
+                if let Some(effect) = unit.deathrattle {
+                    self.apply_effect(effect, i, j);
+                }
+            }
+        }
+    }
+
+    // Rebuilds every unit's aura bonus from the aura units currently on the board
+    fn recompute_auras(&mut self) {
+        for row in &mut self.board {
+            let mut bonus = [(0i8, 0i8); (MAX_ROW + 1) as usize];
+
+            for (source, tile) in row.iter().enumerate() {
+                let aura = match tile {
+                    Some(Card { aura: Some(aura), .. }) => *aura,
+                    _ => continue,
+                };
+                for (pos, (atk, hp)) in bonus.iter_mut().enumerate() {
+                    if aura.applies_to(source, pos) {
+                        let (aura_atk, aura_hp) = aura.stats();
+                        *atk = atk.saturating_add(aura_atk);
+                        *hp = hp.saturating_add(aura_hp);
+                    }
+                }
+            }
+
+            for (tile, (atk, hp)) in row.iter_mut().zip(bonus) {
+                if let Some(unit) = tile {
+                    let alive = unit.effective_hp() > 0;
+                    unit.aura_atk = atk;
+                    unit.aura_hp = hp;
+                    // Losing an aura never kills a unit that was still standing
+                    if alive && unit.effective_hp() <= 0 {
+                        unit.hp = 1i8.saturating_sub(unit.aura_hp);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+// Rules of the card game with no dependency on anchor or solana
+// The on-chain program keeps its accounts in its own types and runs every move through here
+#![no_std]
+
+extern crate alloc;
+
+pub mod card;
+pub mod error;
+pub mod game;
+
+pub use card::*;
+pub use error::*;
+pub use game::*;
@@ -0,0 +1,66 @@
+// Attack coordinates are relative to the attacking player, everything off the board is rejected
+mod common;
+
+use card_engine::*;
+use common::*;
+
+// Both rows full of ready units, so only the coordinates decide whether an attack goes through
+fn full_board(turn: u8) -> Game {
+    let mut game = empty_game();
+    game.turn = turn;
+    game.board = [[Some(unit(5, 1)); 7]; 2];
+    game
+}
+
+fn targets() -> impl Iterator<Item = AttackTarget> {
+    (0..=u8::MAX).map(|pos| AttackTarget::Unit { pos }).chain([AttackTarget::Hero])
+}
+
+#[test]
+fn every_out_of_bounds_coordinate_is_rejected() {
+    for turn in [1, 2] {
+        let game = full_board(turn);
+
+        for ally_pos in 0..=u8::MAX {
+            for target in targets() {
+                let in_bounds = ally_pos <= MAX_ROW && !matches!(target, AttackTarget::Unit { pos } if pos > MAX_ROW);
+
+                let mut attacked = game.clone();
+                let result = attacked.attack(ally_pos, target);
+                if in_bounds {
+                    assert_eq!(result, Ok(()), "turn {} attack({}, {:?})", turn, ally_pos, target);
+                } else {
+                    let context = format!("turn {} attack({}, {:?})", turn, ally_pos, target);
+                    assert_eq!(result, Err(EngineError::PositionOutOfBounds), "{}", context);
+                    assert_eq!(attacked, game);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn player_one_attacks_from_the_bottom_row() {
+    let mut game = full_board(1);
+    game.board[1][2] = Some(unit(5, 3));
+
+    game.attack(2, AttackTarget::Unit { pos: 4 }).unwrap();
+    assert_eq!(game.board[1][2].unwrap().hp, 4);
+    assert_eq!(game.board[0][4].unwrap().hp, 2);
+
+    game.attack(3, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 1]);
+}
+
+#[test]
+fn player_two_attacks_from_the_top_row() {
+    let mut game = full_board(2);
+    game.board[0][2] = Some(unit(5, 3));
+
+    game.attack(2, AttackTarget::Unit { pos: 4 }).unwrap();
+    assert_eq!(game.board[0][2].unwrap().hp, 4);
+    assert_eq!(game.board[1][4].unwrap().hp, 2);
+
+    game.attack(3, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH - 1, STARTING_HEALTH]);
+}
@@ -0,0 +1,88 @@
+// Aura bonuses following their source around the board
+mod common;
+
+use card_engine::*;
+use common::*;
+
+fn with_aura(aura: Aura) -> Card {
+    Card { aura: Some(aura), mana: 0, ..unit(1, 0) }
+}
+
+// (effective atk, effective hp) of every tile on a row, empty tiles read as (0, 0)
+fn row_stats(game: &Game, row: usize) -> Vec<(i8, i8)> {
+    game.board[row].iter().map(|tile| tile.map_or((0, 0), |unit| (unit.effective_atk(), unit.effective_hp()))).collect()
+}
+
+#[test]
+fn adjacent_aura_applies_when_played_and_goes_with_its_source() {
+    let mut game = empty_game();
+    game.hands[0] = vec![with_aura(Aura::Adjacent { atk: 1, hp: 2 }), Card { mana: 0, ..unit(1, 1) }];
+    game.board[1][0] = Some(unit(3, 1));
+    game.board[1][2] = Some(unit(3, 1));
+    game.board[1][4] = Some(unit(3, 1));
+    game.board[0][1] = Some(unit(3, 1));
+
+    game.play_card(1, 0, None).unwrap();
+    assert_eq!(row_stats(&game, 1), [(2, 5), (0, 1), (2, 5), (0, 0), (1, 3), (0, 0), (0, 0)]);
+    // The enemy unit facing the aura doesn't get anything
+    assert_eq!(row_stats(&game, 0)[1], (1, 3));
+
+    // A unit played next to the source later picks the bonus up too
+    game.play_card(3, 0, None).unwrap();
+    assert_eq!(row_stats(&game, 1)[3], (1, 1));
+    game.hands[0] = vec![with_aura(Aura::Adjacent { atk: 1, hp: 2 })];
+    game.play_card(5, 0, None).unwrap();
+    assert_eq!(row_stats(&game, 1)[4], (2, 5));
+
+    game.end_turn().unwrap();
+    game.attack(1, AttackTarget::Unit { pos: 1 }).unwrap();
+    assert_eq!(game.board[1][1], None);
+    assert_eq!(row_stats(&game, 1), [(1, 3), (0, 0), (1, 3), (1, 1), (2, 5), (0, 1), (0, 0)]);
+}
+
+#[test]
+fn other_allies_aura_covers_the_whole_row_but_its_source() {
+    let mut game = empty_game();
+    game.hands[0] = vec![with_aura(Aura::OtherAllies { atk: 2, hp: 1 })];
+    game.board[1][0] = Some(unit(3, 1));
+    game.board[1][6] = Some(unit(3, 1));
+    game.board[0][6] = Some(unit(3, 1));
+
+    game.play_card(3, 0, None).unwrap();
+    assert_eq!(row_stats(&game, 1), [(3, 4), (0, 0), (0, 0), (0, 1), (0, 0), (0, 0), (3, 4)]);
+    assert_eq!(row_stats(&game, 0)[6], (1, 3));
+}
+
+#[test]
+fn auras_stack_across_sources_but_not_across_turns() {
+    let mut game = empty_game();
+    game.board[1][1] = Some(with_aura(Aura::Adjacent { atk: 1, hp: 1 }));
+    game.board[1][2] = Some(unit(3, 1));
+    game.board[1][3] = Some(with_aura(Aura::Adjacent { atk: 1, hp: 1 }));
+
+    // Units put on the board directly pick their auras up on the next end_turn
+    for _ in 0..4 {
+        game.end_turn().unwrap();
+        assert_eq!(row_stats(&game, 1)[2], (3, 5));
+    }
+}
+
+#[test]
+fn losing_an_aura_never_kills_a_unit() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(unit(5, 3));
+    game.board[0][0] = Some(with_aura(Aura::Adjacent { atk: 0, hp: 3 }));
+    game.board[0][1] = Some(unit(1, 0));
+    game.end_turn().unwrap();
+    game.end_turn().unwrap();
+    assert_eq!(row_stats(&game, 0)[1], (0, 4));
+
+    // Damage that only the aura's hp was soaking up
+    game.board[0][1].as_mut().unwrap().take_damage(2);
+    assert_eq!(row_stats(&game, 0)[1], (0, 2));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.board[0][0], None);
+    let survivor = game.board[0][1].unwrap();
+    assert_eq!((survivor.hp, survivor.aura_hp, survivor.effective_hp()), (1, 0, 1));
+}
@@ -0,0 +1,82 @@
+// Battlecries resolving as their unit is played
+mod common;
+
+use card_engine::*;
+use common::*;
+
+fn with_battlecry(effect: Effect) -> Card {
+    Card { battlecry: Some(effect), mana: 0, moves: 0, ..unit(2, 2) }
+}
+
+fn game_with_hand(hand: Vec<Card>) -> Game {
+    let mut game = empty_game();
+    game.hands[0] = hand;
+    game
+}
+
+#[test]
+fn targeted_battlecry_resolves_on_its_target() {
+    let mut game = game_with_hand(vec![with_battlecry(Effect::Damage { amount: 3 })]);
+    game.board[0][2] = Some(unit(5, 1));
+
+    game.play_card(0, 0, Some(EffectTarget { row: 0, pos: 2 })).unwrap();
+    assert_eq!(game.board[0][2].unwrap().hp, 2);
+    assert_eq!(game.board[1][0].unwrap().hp, 2);
+    assert!(game.hands[0].is_empty());
+}
+
+#[test]
+fn battlecry_can_target_the_unit_being_played() {
+    let mut game = game_with_hand(vec![with_battlecry(Effect::Buff { atk: 1, hp: 1 })]);
+
+    game.play_card(3, 0, Some(EffectTarget { row: 1, pos: 3 })).unwrap();
+    let unit = game.board[1][3].unwrap();
+    assert_eq!((unit.atk, unit.hp), (3, 3));
+}
+
+#[test]
+fn untargeted_battlecry_resolves_around_the_played_unit() {
+    let mut game = game_with_hand(vec![with_battlecry(Effect::BuffAdjacent { atk: 1, hp: 0 })]);
+    game.board[1][2] = Some(unit(1, 1));
+    game.board[1][4] = Some(unit(1, 1));
+    game.board[1][5] = Some(unit(1, 1));
+
+    // A target sent along with an untargeted battlecry is ignored
+    game.play_card(3, 0, Some(EffectTarget { row: 0, pos: 0 })).unwrap();
+    let atk: Vec<i8> = game.board[1].iter().map(|tile| tile.map_or(0, |unit| unit.atk)).collect();
+    assert_eq!(atk, [0, 0, 2, 2, 2, 1, 0]);
+}
+
+#[test]
+fn rejected_battlecry_target_keeps_the_card_in_hand() {
+    let card = with_battlecry(Effect::Damage { amount: 3 });
+    let mut game = game_with_hand(vec![card]);
+    let before = game.clone();
+
+    assert_eq!(game.play_card(0, 0, None), Err(EngineError::MissingEffectTarget));
+    assert_eq!(game.play_card(0, 0, Some(EffectTarget { row: 0, pos: 2 })), Err(EngineError::EmptyBoardSpace));
+    assert_eq!(game, before);
+}
+
+#[test]
+fn lethal_battlecry_ends_the_game() {
+    let mut game = game_with_hand(vec![with_battlecry(Effect::DamageEnemyHero { amount: 4 })]);
+    game.health[1] = 4;
+
+    game.play_card(0, 0, None).unwrap();
+    assert_eq!(game.health[1], 0);
+    assert_eq!(game.state, GameState::Won { winner: 0 });
+    assert_eq!(game.end_turn(), Err(EngineError::GameAlreadyOver));
+}
+
+#[test]
+fn draw_card_battlecry_draws_for_the_player_who_played_it() {
+    let drawn = unit(7, 7);
+    let mut game = game_with_hand(vec![with_battlecry(Effect::DrawCard)]);
+    game.decks = [vec![drawn], vec![unit(1, 1)]];
+
+    game.play_card(0, 0, None).unwrap();
+    assert_eq!(game.hands[0], vec![drawn]);
+    assert!(game.decks[0].is_empty());
+    assert_eq!(game.decks[1].len(), 1);
+}
@@ -0,0 +1,14 @@
+// Shared fixtures for the rules tests, games are set up on player 1's turn
+#![allow(dead_code)]
+
+use card_engine::*;
+
+// Ready unit without keywords, the same as a summoned token
+pub fn unit(hp: i8, atk: i8) -> Card {
+    Card::token(hp, atk, true)
+}
+
+// No decks, hands or units yet
+pub fn empty_game() -> Game {
+    Game::new([Vec::new(), Vec::new()], 1)
+}
@@ -0,0 +1,87 @@
+// Deathrattles and the order dead units are cleared in
+mod common;
+
+use card_engine::*;
+use common::*;
+
+fn with_deathrattle(hp: i8, atk: i8, effect: Effect) -> Card {
+    Card { deathrattle: Some(effect), ..unit(hp, atk) }
+}
+
+#[test]
+fn deathrattle_summons_into_the_freed_tile() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(unit(5, 3));
+    game.board[0][4] = Some(with_deathrattle(1, 1, Effect::Summon { hp: 2, atk: 1 }));
+
+    game.attack(0, AttackTarget::Unit { pos: 4 }).unwrap();
+    let token = game.board[0][4].unwrap();
+    assert_eq!((token.hp, token.atk, token.deathrattle), (2, 1, None));
+}
+
+#[test]
+fn attacker_side_resolves_first() {
+    // Healing before the damage would be wasted on a hero that is already close to full
+    let mut game = empty_game();
+    game.health[1] = STARTING_HEALTH - 2;
+    game.board[1][0] = Some(with_deathrattle(1, 1, Effect::DamageEnemyHero { amount: 5 }));
+    game.board[0][0] = Some(with_deathrattle(1, 1, Effect::HealOwnHero { amount: 5 }));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 2]);
+}
+
+#[test]
+fn units_dying_together_resolve_left_to_right() {
+    // Units left at 0 hp die together on the next board update
+    let mut game = empty_game();
+    game.hands[0] = vec![Card { mana: 0, ..unit(1, 1) }];
+    game.board[1][2] = Some(with_deathrattle(0, 1, Effect::Summon { hp: 1, atk: 1 }));
+    game.board[1][3] = Some(with_deathrattle(0, 1, Effect::BuffAdjacent { atk: 2, hp: 0 }));
+
+    game.play_card(6, 0, None).unwrap();
+    // The token from the left unit is already there when the right unit's buff goes off
+    assert_eq!(game.board[1][2].unwrap().atk, 3);
+    assert_eq!(game.board[1][3], None);
+}
+
+#[test]
+fn chained_deaths_resolve_until_the_board_is_stable() {
+    let mut game = empty_game();
+    game.board[1][1] = Some(with_deathrattle(1, 1, Effect::BuffAdjacent { atk: 0, hp: -5 }));
+    game.board[1][0] = Some(with_deathrattle(2, 1, Effect::DamageEnemyHero { amount: 3 }));
+    game.board[1][2] = Some(with_deathrattle(2, 1, Effect::Summon { hp: 2, atk: 2 }));
+    game.board[0][0] = Some(unit(1, 1));
+
+    game.attack(1, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.board[0][0], None);
+    assert_eq!(game.board[1][0], None);
+    assert_eq!(game.board[1][1], None);
+    // The token arrives after the debuff that killed its summoner, so it survives
+    assert_eq!(game.board[1][2].unwrap().hp, 2);
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 3]);
+}
+
+#[test]
+fn both_heroes_dying_together_is_a_tie() {
+    let mut game = empty_game();
+    game.health = [3, 3];
+    game.board[1][0] = Some(with_deathrattle(1, 1, Effect::DamageEnemyHero { amount: 5 }));
+    game.board[0][0] = Some(with_deathrattle(1, 1, Effect::DamageEnemyHero { amount: 5 }));
+    game.board[1][1] = Some(unit(5, 5));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.health, [-2, -2]);
+    assert_eq!(game.state, GameState::Tie);
+    assert_eq!(game.attack(1, AttackTarget::Hero), Err(EngineError::GameAlreadyOver));
+}
+
+#[test]
+fn hero_health_bottoms_out_instead_of_overflowing() {
+    let mut game = empty_game();
+    game.health[1] = i8::MIN + 1;
+    game.board[1][0] = Some(unit(5, i8::MAX));
+
+    game.attack(0, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health[1], i8::MIN);
+}
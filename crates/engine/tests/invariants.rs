@@ -0,0 +1,172 @@
+// Random games played through the public rules, checking what has to hold after every action
+// Actions are drawn from everything a client can send, so most of them get rejected
+use card_engine::*;
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Action {
+    PlayCard { pos: u8, card_index: u8, target: Option<EffectTarget> },
+    CastSpell { card_index: u8, row: u8, pos: u8 },
+    Attack { ally_pos: u8, target: AttackTarget },
+    EndTurn,
+}
+
+fn effect() -> impl Strategy<Value = Effect> {
+    prop_oneof![
+        (0..=6i8).prop_map(|amount| Effect::Damage { amount }),
+        (0..=6i8).prop_map(|amount| Effect::Heal { amount }),
+        (0..=3i8, -2..=3i8).prop_map(|(atk, hp)| Effect::Buff { atk, hp }),
+        (0..=6i8).prop_map(|amount| Effect::HealOwnHero { amount }),
+        (0..=3i8, -2..=3i8).prop_map(|(atk, hp)| Effect::BuffAdjacent { atk, hp }),
+        (0..=4i8, 0..=4i8).prop_map(|(hp, atk)| Effect::Summon { hp, atk }),
+        (0..=6i8).prop_map(|amount| Effect::DamageEnemyHero { amount }),
+        (0..=6i8).prop_map(|amount| Effect::DamageRandomEnemy { amount }),
+        Just(Effect::DrawCard),
+    ]
+}
+
+fn aura() -> impl Strategy<Value = Aura> {
+    prop_oneof![
+        (0..=2i8, -1..=2i8).prop_map(|(atk, hp)| Aura::Adjacent { atk, hp }),
+        (0..=2i8, -1..=2i8).prop_map(|(atk, hp)| Aura::OtherAllies { atk, hp }),
+    ]
+}
+
+// Cards as the registry hands them out, before anything happened to them in game
+fn card() -> impl Strategy<Value = Card> {
+    let kind = prop_oneof![3 => Just(CardKind::Unit), 1 => effect().prop_map(|effect| CardKind::Spell { effect })];
+    let keywords = (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>());
+    let effects = (proptest::option::of(effect()), proptest::option::of(effect()), proptest::option::of(aura()));
+
+    (1..=8i8, 0..=8i8, 0..=MAX_MANA, keywords, kind, effects).prop_map(
+        |(hp, atk, mana, (taunt, charge, rush, divine_shield), kind, (battlecry, deathrattle, aura))| Card {
+            hp,
+            max_hp: hp,
+            atk,
+            mana,
+            moves: 0,
+            taunt,
+            charge,
+            rush,
+            summoning_sick: false,
+            divine_shield,
+            kind,
+            battlecry,
+            deathrattle,
+            aura,
+            aura_atk: 0,
+            aura_hp: 0,
+        },
+    )
+}
+
+fn deck() -> impl Strategy<Value = Vec<Card>> {
+    proptest::collection::vec(card(), 0..=30)
+}
+
+// Mostly on the board, with the odd position anywhere in range
+fn attack_pos() -> impl Strategy<Value = u8> {
+    prop_oneof![4 => 0..=MAX_ROW, 1 => any::<u8>()]
+}
+
+fn attack_target() -> impl Strategy<Value = AttackTarget> {
+    prop_oneof![
+        3 => attack_pos().prop_map(|pos| AttackTarget::Unit { pos }),
+        1 => Just(AttackTarget::Hero),
+    ]
+}
+
+// Out of range values are included on purpose, rows go one past the board and positions one past the hero
+fn action() -> impl Strategy<Value = Action> {
+    let target = proptest::option::of((0..=2u8, 0..=HERO_POS + 1).prop_map(|(row, pos)| EffectTarget { row, pos }));
+
+    prop_oneof![
+        3 => (0..=HERO_POS, 0..=MAX_HAND as u8, target)
+            .prop_map(|(pos, card_index, target)| Action::PlayCard { pos, card_index, target }),
+        2 => (0..=MAX_HAND as u8, 0..=2u8, 0..=HERO_POS + 1)
+            .prop_map(|(card_index, row, pos)| Action::CastSpell { card_index, row, pos }),
+        3 => (attack_pos(), attack_target()).prop_map(|(ally_pos, target)| Action::Attack { ally_pos, target }),
+        2 => Just(Action::EndTurn),
+    ]
+}
+
+fn apply(game: &mut Game, action: &Action) -> Result<()> {
+    match *action {
+        Action::PlayCard { pos, card_index, target } => game.play_card(pos, card_index, target),
+        Action::CastSpell { card_index, row, pos } => game.cast_spell(card_index, row, pos),
+        Action::Attack { ally_pos, target } => game.attack(ally_pos, target),
+        Action::EndTurn => game.end_turn(),
+    }
+}
+
+fn check_invariants(before: &Game, after: &Game, action: &Action, result: Result<()>) -> core::result::Result<(), TestCaseError> {
+    for mana in after.mana {
+        prop_assert!((0..=MAX_MANA).contains(&mana), "mana {} out of range after {:?}", mana, action);
+    }
+
+    for unit in after.board.iter().flatten().flatten() {
+        prop_assert!(unit.effective_hp() > 0, "dead unit {:?} left on the board after {:?}", unit, action);
+    }
+
+    if after.health.iter().any(|&health| health <= 0) {
+        prop_assert!(!after.is_active(), "hero down to {:?} but the game goes on after {:?}", after.health, action);
+    }
+
+    if !before.is_active() {
+        prop_assert_eq!(after.state, before.state, "finished game changed state after {:?}", action);
+    }
+
+    // Hands only shrink, apart from cards drawn off the owner's deck at the start of a turn or by an effect
+    for player in 0..2 {
+        prop_assert!(after.hands[player].len() <= MAX_HAND);
+        prop_assert!(after.decks[player].len() <= before.decks[player].len());
+
+        let drawn = before.decks[player].len() - after.decks[player].len();
+        prop_assert!(
+            after.hands[player].len() <= before.hands[player].len() + drawn,
+            "hand of player {} grew without drawing after {:?}",
+            player,
+            action
+        );
+    }
+
+    // A rejected action leaves nothing behind
+    if result.is_err() {
+        prop_assert_eq!(after, before, "{:?} failed with {:?} but changed the game", action, result);
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn invariants_hold_for_any_sequence_of_actions(
+        decks in (deck(), deck()),
+        seed in any::<u64>(),
+        actions in proptest::collection::vec(action(), 0..600),
+    ) {
+        let mut game = Game::new([decks.0, decks.1], seed);
+
+        for action in &actions {
+            let before = game.clone();
+            let result = apply(&mut game, action);
+            check_invariants(&before, &game, action, result)?;
+        }
+    }
+
+    // Turns only ever end, so every game runs into the turn counter's limits
+    #[test]
+    fn invariants_hold_when_players_only_end_turns(
+        decks in (deck(), deck()),
+        seed in any::<u64>(),
+        turns in 0..600usize,
+    ) {
+        let mut game = Game::new([decks.0, decks.1], seed);
+
+        for _ in 0..turns {
+            let before = game.clone();
+            let result = apply(&mut game, &Action::EndTurn);
+            check_invariants(&before, &game, &Action::EndTurn, result)?;
+        }
+    }
+}
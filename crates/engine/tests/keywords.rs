@@ -0,0 +1,155 @@
+// Unit keywords as the rules apply them
+mod common;
+
+use card_engine::*;
+use common::*;
+
+// Unit card as it sits in a hand, before play_card decides whether it is ready
+fn in_hand(keywords: Card) -> Card {
+    Card { mana: 0, moves: 0, summoning_sick: false, ..keywords }
+}
+
+#[test]
+fn taunt_units_have_to_be_attacked_first() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(unit(5, 2));
+    game.board[1][1] = Some(unit(5, 2));
+    game.board[0][3] = Some(unit(5, 1));
+    game.board[0][4] = Some(Card { taunt: true, ..unit(2, 1) });
+    let before = game.clone();
+
+    assert_eq!(game.attack(0, AttackTarget::Unit { pos: 3 }), Err(EngineError::MustAttackTauntUnit));
+    assert_eq!(game.attack(0, AttackTarget::Hero), Err(EngineError::MustAttackTauntUnit));
+    assert_eq!(game, before);
+
+    game.attack(0, AttackTarget::Unit { pos: 4 }).unwrap();
+    assert_eq!(game.board[0][4], None);
+
+    // With the taunt unit gone the rest of the row and the hero are open again
+    game.attack(1, AttackTarget::Unit { pos: 3 }).unwrap();
+    assert_eq!(game.board[0][3].unwrap().hp, 3);
+}
+
+#[test]
+fn taunt_only_guards_its_own_row() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(Card { taunt: true, ..unit(5, 2) });
+    game.board[0][0] = Some(unit(5, 2));
+
+    game.attack(0, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 2]);
+}
+
+#[test]
+fn units_without_charge_or_rush_wait_a_turn() {
+    let mut game = empty_game();
+    // Whatever readiness the card comes with is ignored
+    game.hands[0] = vec![Card { moves: 1, ..in_hand(unit(3, 3)) }];
+    game.board[0][0] = Some(unit(5, 1));
+
+    game.play_card(0, 0, None).unwrap();
+    assert_eq!(game.attack(0, AttackTarget::Unit { pos: 0 }), Err(EngineError::UnitIsNotReady));
+    assert_eq!(game.attack(0, AttackTarget::Hero), Err(EngineError::UnitIsNotReady));
+
+    game.end_turn().unwrap();
+    game.end_turn().unwrap();
+    game.attack(0, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health[1], STARTING_HEALTH - 3);
+}
+
+#[test]
+fn charge_units_can_attack_anything_the_turn_they_are_played() {
+    let mut game = empty_game();
+    game.hands[0] = vec![in_hand(Card { charge: true, ..unit(3, 3) })];
+
+    game.play_card(0, 0, None).unwrap();
+    game.attack(0, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health[1], STARTING_HEALTH - 3);
+
+    // Still only one attack per turn
+    assert_eq!(game.attack(0, AttackTarget::Hero), Err(EngineError::UnitIsNotReady));
+}
+
+#[test]
+fn rush_units_can_only_attack_units_the_turn_they_are_played() {
+    let mut game = empty_game();
+    game.hands[0] = vec![in_hand(Card { rush: true, ..unit(3, 3) })];
+    game.board[0][0] = Some(unit(5, 1));
+
+    game.play_card(0, 0, None).unwrap();
+    let before = game.clone();
+    assert_eq!(game.attack(0, AttackTarget::Hero), Err(EngineError::UnitIsNotReady));
+    assert_eq!(game, before);
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.board[0][0].unwrap().hp, 2);
+
+    // From its next turn on it is a regular unit
+    game.end_turn().unwrap();
+    game.end_turn().unwrap();
+    game.attack(0, AttackTarget::Hero).unwrap();
+    assert_eq!(game.health[1], STARTING_HEALTH - 3);
+}
+
+fn shielded(hp: i8, atk: i8) -> Card {
+    Card { divine_shield: true, ..unit(hp, atk) }
+}
+
+fn damage_spell(amount: i8) -> Card {
+    Card { kind: CardKind::Spell { effect: Effect::Damage { amount } }, ..in_hand(unit(1, 0)) }
+}
+
+#[test]
+fn divine_shield_absorbs_the_first_hit_on_a_defender() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(unit(5, 3));
+    game.board[1][1] = Some(unit(5, 3));
+    game.board[0][0] = Some(shielded(2, 1));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    let defender = game.board[0][0].unwrap();
+    assert_eq!((defender.hp, defender.divine_shield), (2, false));
+    assert_eq!(game.board[1][0].unwrap().hp, 4);
+
+    game.attack(1, AttackTarget::Unit { pos: 0 }).unwrap();
+    assert_eq!(game.board[0][0], None);
+}
+
+#[test]
+fn divine_shield_absorbs_the_counter_attack_on_an_attacker() {
+    let mut game = empty_game();
+    game.board[1][0] = Some(shielded(2, 1));
+    game.board[0][0] = Some(unit(5, 3));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    let attacker = game.board[1][0].unwrap();
+    assert_eq!((attacker.hp, attacker.divine_shield), (2, false));
+    assert_eq!(game.board[0][0].unwrap().hp, 4);
+}
+
+#[test]
+fn divine_shield_absorbs_spell_damage() {
+    let mut game = empty_game();
+    game.hands[0] = vec![damage_spell(4), damage_spell(4)];
+    game.board[0][0] = Some(shielded(2, 1));
+
+    game.cast_spell(0, 0, 0).unwrap();
+    let unit = game.board[0][0].unwrap();
+    assert_eq!((unit.hp, unit.divine_shield), (2, false));
+
+    game.cast_spell(0, 0, 0).unwrap();
+    assert_eq!(game.board[0][0], None);
+}
+
+#[test]
+fn zero_damage_leaves_divine_shield_in_place() {
+    let mut game = empty_game();
+    game.hands[0] = vec![damage_spell(0)];
+    game.board[1][0] = Some(unit(5, 0));
+    game.board[0][0] = Some(shielded(2, 0));
+
+    game.attack(0, AttackTarget::Unit { pos: 0 }).unwrap();
+    game.cast_spell(0, 0, 0).unwrap();
+    let unit = game.board[0][0].unwrap();
+    assert_eq!((unit.hp, unit.divine_shield), (2, true));
+}
@@ -0,0 +1,103 @@
+// Spells resolving on units and heroes
+mod common;
+
+use card_engine::*;
+use common::*;
+
+fn spell(effect: Effect, mana: i8) -> Card {
+    Card { kind: CardKind::Spell { effect }, mana, ..unit(1, 0) }
+}
+
+// Player 1 holds the spells with mana to spare, both rows have a unit at pos 0
+fn game_with_hand(hand: Vec<Card>) -> Game {
+    let mut game = empty_game();
+    game.mana = [5, 5];
+    game.hands[0] = hand;
+    game.board[0][0] = Some(unit(5, 1));
+    game.board[1][0] = Some(unit(5, 1));
+    game
+}
+
+#[test]
+fn casting_spends_mana_and_removes_the_card_from_hand() {
+    let fireball = spell(Effect::Damage { amount: 2 }, 3);
+    let heal = spell(Effect::Heal { amount: 2 }, 1);
+    let mut game = game_with_hand(vec![heal, fireball]);
+
+    game.cast_spell(1, 0, 0).unwrap();
+    assert_eq!(game.hands[0], vec![heal]);
+    assert_eq!(game.mana, [2, 5]);
+    assert_eq!(game.board[0][0].unwrap().hp, 3);
+
+    // Spells never take up a tile
+    assert!(game.board.iter().flatten().flatten().all(|unit| !unit.is_spell()));
+}
+
+#[test]
+fn damage_and_heal_land_on_heroes() {
+    let mut game = game_with_hand(vec![
+        spell(Effect::Damage { amount: 4 }, 0),
+        spell(Effect::Heal { amount: 3 }, 0),
+        spell(Effect::Heal { amount: 3 }, 0),
+    ]);
+
+    game.cast_spell(0, 0, HERO_POS).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 4]);
+
+    game.cast_spell(0, 0, HERO_POS).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH - 1]);
+
+    // Heroes don't heal past their starting health
+    game.cast_spell(0, 0, HERO_POS).unwrap();
+    assert_eq!(game.health, [STARTING_HEALTH, STARTING_HEALTH]);
+}
+
+#[test]
+fn lethal_damage_spell_ends_the_game() {
+    let mut game = game_with_hand(vec![spell(Effect::Damage { amount: 5 }, 0)]);
+    game.health[1] = 5;
+
+    game.cast_spell(0, 0, HERO_POS).unwrap();
+    assert_eq!(game.state, GameState::Won { winner: 0 });
+}
+
+#[test]
+fn heals_stop_at_a_units_max_hp() {
+    let mut game = game_with_hand(vec![
+        spell(Effect::Damage { amount: 3 }, 0),
+        spell(Effect::Heal { amount: 2 }, 0),
+        spell(Effect::Heal { amount: 2 }, 0),
+    ]);
+
+    game.cast_spell(0, 1, 0).unwrap();
+    game.cast_spell(0, 1, 0).unwrap();
+    assert_eq!(game.board[1][0].unwrap().hp, 4);
+
+    game.cast_spell(0, 1, 0).unwrap();
+    assert_eq!(game.board[1][0].unwrap().hp, 5);
+}
+
+#[test]
+fn buffs_raise_max_hp_along_with_hp() {
+    let mut game = game_with_hand(vec![
+        spell(Effect::Buff { atk: 2, hp: 3 }, 0),
+        spell(Effect::Damage { amount: 6 }, 0),
+        spell(Effect::Heal { amount: 10 }, 0),
+    ]);
+
+    game.cast_spell(0, 1, 0).unwrap();
+    let unit = game.board[1][0].unwrap();
+    assert_eq!((unit.atk, unit.hp, unit.max_hp), (3, 8, 8));
+
+    game.cast_spell(0, 1, 0).unwrap();
+    game.cast_spell(0, 1, 0).unwrap();
+    assert_eq!(game.board[1][0].unwrap().hp, 8);
+}
+
+#[test]
+fn units_are_played_at_full_max_hp() {
+    let mut game = game_with_hand(vec![Card { max_hp: 20, ..unit(3, 1) }]);
+
+    game.play_card(1, 0, None).unwrap();
+    assert_eq!(game.board[1][1].unwrap().max_hp, 3);
+}
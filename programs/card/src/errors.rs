@@ -25,4 +25,52 @@ pub enum CardGameError {
     GameStillActive,
     MismatchPlayerKeys,
     CardIndexOutOfBounds,
+    MustAttackTauntUnit,
+    SpellCannotBePlaced,
+    CardIsNotASpell,
+    InvalidEffectTarget,
+    MissingEffectTarget,
+    DeckTooLarge,
+    UnknownCardId,
+    CardRegistryFull,
+    InvalidCardDefinition,
+    NotRegistryAdmin,
+    ChallengeNotPending,
+    GameAlreadySettled,
+    NotPlayersActiveGame,
+    InvalidTurnLimit,
+    TurnNotTimedOut,
+    CannotClaimOwnTurn,
+    GameNotSettled,
+    InvalidSeasonDuration,
+    SeasonNotOver,
+    NotSeasonAdmin,
+    InvalidSeedReveal,
+    SeedAlreadyRevealed,
+    NotRevealingSeeds,
+    NotUpgradeAuthority,
+    AccountAlreadyMigrated,
+    TurnSeedNotRevealed,
+}
+
+impl From<card_engine::EngineError> for CardGameError {
+    fn from(error: card_engine::EngineError) -> Self {
+        use card_engine::EngineError;
+
+        match error {
+            EngineError::TileOutOfBounds => CardGameError::TileOutOfBounds,
+            EngineError::PositionOutOfBounds => CardGameError::PositionOutOfBounds,
+            EngineError::EmptyBoardSpace => CardGameError::EmptyBoardSpace,
+            EngineError::TileAlreadySet => CardGameError::TileAlreadySet,
+            EngineError::GameAlreadyOver => CardGameError::GameAlreadyOver,
+            EngineError::UnitIsNotReady => CardGameError::UnitIsNotReady,
+            EngineError::InsufficientMana => CardGameError::InsufficientMana,
+            EngineError::CardIndexOutOfBounds => CardGameError::CardIndexOutOfBounds,
+            EngineError::MustAttackTauntUnit => CardGameError::MustAttackTauntUnit,
+            EngineError::SpellCannotBePlaced => CardGameError::SpellCannotBePlaced,
+            EngineError::CardIsNotASpell => CardGameError::CardIsNotASpell,
+            EngineError::InvalidEffectTarget => CardGameError::InvalidEffectTarget,
+            EngineError::MissingEffectTarget => CardGameError::MissingEffectTarget,
+        }
+    }
 }
\ No newline at end of file
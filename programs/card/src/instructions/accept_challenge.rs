@@ -0,0 +1,56 @@
+use crate::state::user::*;
+use crate::state::game::*;
+use crate::state::deck::*;
+use crate::state::card_registry::*;
+use crate::errors::CardGameError;
+use anchor_lang::prelude::*;
+
+// Player 2 brings their deck and seed commitment, the game starts once both seeds are revealed
+pub fn accept_challenge(ctx: Context<AcceptChallenge>, commitment: [u8; 32]) -> Result<()> {
+    match ctx.accounts{
+        AcceptChallenge {
+            game,
+            p1,
+            p2,
+            p2_stats,
+            p2_deck,
+            card_registry,
+        } => {
+
+            if game.get_players() != [p1.key(), p2.key()] {
+                return Err(CardGameError::MismatchPlayerKeys.into())
+            }
+
+            if p2_stats.check_active_game().is_some() {
+                return Err(CardGameError::YouHaveAnActiveGame.into())
+            }
+
+            p2_stats.set_active_game(game.key());
+
+            let p2_cards = p2_deck.resolve(card_registry)?;
+            game.accept_challenge(p2_cards, commitment, Clock::get()?.unix_timestamp)
+        }
+    }
+
+}
+
+
+#[derive(Accounts)]
+pub struct AcceptChallenge<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    /// CHECK: Only taking p1 pubkey to check the challenge is from them
+    pub p1: UncheckedAccount<'info>,
+    pub p2: Signer<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", p2.key().as_ref()], bump
+    )]
+    pub p2_stats: Account<'info, UserStats>,
+    #[account(
+        seeds=[b"deck", p2.key().as_ref(), &[p2_deck.index]], bump = p2_deck.bump
+    )]
+    pub p2_deck: Account<'info, Deck>,
+    #[account(seeds = [b"card-registry"], bump = card_registry.bump)]
+    pub card_registry: Account<'info, CardRegistry>,
+}
\ No newline at end of file
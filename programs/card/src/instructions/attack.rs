@@ -2,7 +2,7 @@ use crate::errors::CardGameError;
 use crate::state::game::*;
 use anchor_lang::prelude::*;
 
-pub fn attack(ctx: Context<Attack>, bot_pos: u8, top_pos: u8) -> Result<()> {
+pub fn attack(ctx: Context<Attack>, ally_pos: u8, target: AttackTarget) -> Result<()> {
     let game = &mut ctx.accounts.game;
 
     require_keys_eq!(
@@ -11,7 +11,7 @@ pub fn attack(ctx: Context<Attack>, bot_pos: u8, top_pos: u8) -> Result<()> {
         CardGameError::NotPlayersTurn
     );
 
-    game.attack(bot_pos, top_pos)
+    game.attack(ally_pos, target)
 }
 
 #[derive(Accounts)]
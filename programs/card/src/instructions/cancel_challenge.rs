@@ -0,0 +1,52 @@
+use crate::state::user::*;
+use crate::state::game::*;
+use crate::errors::CardGameError;
+use anchor_lang::prelude::*;
+
+// Player 1 withdraws a challenge that hasn't been accepted, refunding the game account
+pub fn cancel_challenge(ctx: Context<CancelChallenge>) -> Result<()> {
+    match ctx.accounts{
+        CancelChallenge {
+            game,
+            p1,
+            p1_stats,
+            p2,
+            p2_stats,
+        } => {
+
+            if !game.is_pending() {
+                return Err(CardGameError::ChallengeNotPending.into())
+            }
+
+            if game.get_players() != [p1.key(), p2.key()] {
+                return Err(CardGameError::MismatchPlayerKeys.into())
+            }
+
+            p1_stats.clear_active_if(game.key());
+            p2_stats.clear_active_if(game.key());
+            Ok(())
+        }
+    }
+
+}
+
+
+#[derive(Accounts)]
+pub struct CancelChallenge<'info> {
+    #[account(mut, close = p1)]
+    pub game: Account<'info, Game>,
+    #[account(mut)]
+    pub p1: Signer<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", p1.key().as_ref()], bump
+    )]
+    pub p1_stats: Account<'info, UserStats>,
+    /// CHECK: Only taking p2 pubkey to check that p2_stats belongs to the same player
+    pub p2: UncheckedAccount<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", p2.key().as_ref()], bump
+    )]
+    pub p2_stats: Account<'info, UserStats>,
+}
\ No newline at end of file
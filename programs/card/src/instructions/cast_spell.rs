@@ -0,0 +1,22 @@
+use crate::errors::CardGameError;
+use crate::state::game::*;
+use anchor_lang::prelude::*;
+
+pub fn cast_spell(ctx: Context<CastSpell>, card_index: u8, target_row: u8, target_pos: u8) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    require_keys_eq!(
+        game.current_player(),
+        ctx.accounts.player.key(),
+        CardGameError::NotPlayersTurn
+    );
+
+    game.cast_spell(card_index, target_row, target_pos)
+}
+
+#[derive(Accounts)]
+pub struct CastSpell<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+}
\ No newline at end of file
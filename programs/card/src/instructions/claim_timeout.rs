@@ -0,0 +1,76 @@
+use crate::state::user::*;
+use crate::state::game::*;
+use crate::state::leaderboard::*;
+use crate::state::season::*;
+use crate::errors::CardGameError;
+use anchor_lang::prelude::*;
+
+// The waiting player takes the win when the opponent lets their turn run out
+pub fn claim_timeout(ctx: Context<ClaimTimeout>) -> Result<()> {
+    match ctx.accounts{
+        ClaimTimeout {
+            game,
+            player,
+            player_stats,
+            opponent,
+            opponent_stats,
+            leaderboard,
+            season,
+        } => {
+
+            if game.is_settled() {
+                return Err(CardGameError::GameAlreadySettled.into())
+            }
+
+            if !game.is_waiting_on(opponent.key()) {
+                return Err(CardGameError::MismatchPlayerKeys.into())
+            }
+
+            let game_key = game.key();
+            if player_stats.check_active_game() != Some(game_key)
+                || opponent_stats.check_active_game() != Some(game_key) {
+                return Err(CardGameError::NotPlayersActiveGame.into())
+            }
+
+            game.claim_timeout(player.key(), Clock::get()?.unix_timestamp)?;
+            game.settle()?;
+            player_stats.sync_season(season.number);
+            opponent_stats.sync_season(season.number);
+
+            UserStats::record_win(player_stats, opponent_stats);
+            leaderboard.update_entry(player.key(), player_stats.get_name(), player_stats.get_score());
+            leaderboard.update_entry(opponent.key(), opponent_stats.get_name(), opponent_stats.get_score());
+            player_stats.clear_active();
+            opponent_stats.clear_active();
+
+            Ok(())
+        }
+    }
+
+}
+
+
+#[derive(Accounts)]
+pub struct ClaimTimeout<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", player.key().as_ref()], bump
+    )]
+    pub player_stats: Account<'info, UserStats>,
+    /// CHECK: Only taking opponent pubkey to check that opponent_stats belongs to the same player
+    pub opponent: UncheckedAccount<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", opponent.key().as_ref()], bump
+    )]
+    pub opponent_stats: Account<'info, UserStats>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(seeds = [b"season"], bump = season.bump)]
+    pub season: Account<'info, Season>,
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::state::game::*;
+use crate::errors::CardGameError;
+use anchor_lang::prelude::*;
+
+// Either player can close a settled game, the rent always goes back to p1 who paid for it
+pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+    match ctx.accounts{
+        CloseGame {
+            game,
+            p1,
+            player,
+        } => {
+
+            let players = game.get_players();
+            if players[0] != p1.key() || !players.contains(&player.key()) {
+                return Err(CardGameError::MismatchPlayerKeys.into())
+            }
+
+            game.check_closable()
+        }
+    }
+
+}
+
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(mut, close = p1)]
+    pub game: Account<'info, Game>,
+    #[account(mut)]
+    /// CHECK: Only receives the refund, checked against the game's first player
+    pub p1: UncheckedAccount<'info>,
+    pub player: Signer<'info>,
+}
\ No newline at end of file
@@ -1,29 +1,48 @@
 use crate::state::user::*;
 use crate::state::game::*;
+use crate::state::leaderboard::*;
+use crate::state::season::*;
 use crate::errors::CardGameError;
 use anchor_lang::prelude::*;
 
+// Whichever player signs loses, the game is settled on the spot
 pub fn concede(ctx: Context<Concede>) -> Result<()> {
     match ctx.accounts{
         Concede {
             game,
-            p1,
-            p1_stats,
-            p2,
-            p2_stats,
+            player,
+            player_stats,
+            opponent,
+            opponent_stats,
+            leaderboard,
+            season,
             system_program: _,
         } => {
 
-            if game.match_pubkeys([p1.key(), p2.key()]) {
-                p1_stats.decrease_score();
-                p1_stats.clear_active();
+            if game.is_settled() {
+                return Err(CardGameError::GameAlreadySettled.into())
+            }
 
-                p2_stats.increase_score();
-                p2_stats.clear_active();
-            } else {
+            let winner = game.concede(player.key())?;
+            if winner != opponent.key() {
                 return Err(CardGameError::MismatchPlayerKeys.into())
             }
 
+            let game_key = game.key();
+            if player_stats.check_active_game() != Some(game_key)
+                || opponent_stats.check_active_game() != Some(game_key) {
+                return Err(CardGameError::NotPlayersActiveGame.into())
+            }
+            game.settle()?;
+            player_stats.sync_season(season.number);
+            opponent_stats.sync_season(season.number);
+
+            UserStats::record_win(opponent_stats, player_stats);
+            leaderboard.update_entry(opponent.key(), opponent_stats.get_name(), opponent_stats.get_score());
+            leaderboard.update_entry(player.key(), player_stats.get_name(), player_stats.get_score());
+            player_stats.clear_active();
+            opponent_stats.clear_active();
+
             Ok(())
         }
     }
@@ -36,19 +55,24 @@ pub struct Concede<'info> {
     #[account(mut)]
     pub game: Account<'info, Game>,
     #[account(mut)]
-    pub p1: Signer<'info>,
+    pub player: Signer<'info>,
     #[account(
         mut, 
-        seeds=[b"user-stats", p1.key().as_ref()], bump
+        seeds=[b"user-stats", player.key().as_ref()], bump
     )]
-    pub p1_stats: Account<'info, UserStats>,
-    #[account(mut)]
-    /// CHECK: Only taking p2 pubkey to check that p2_stats belongs to the same player
-    pub p2: UncheckedAccount<'info>,
+    pub player_stats: Account<'info, UserStats>,
+    /// CHECK: Only taking opponent pubkey to check that opponent_stats belongs to the same player
+    pub opponent: UncheckedAccount<'info>,
     #[account(
         mut, 
-        seeds=[b"user-stats", p2.key().as_ref()], bump
+        seeds=[b"user-stats", opponent.key().as_ref()], bump
     )]
-    pub p2_stats: Account<'info, UserStats>,
+    pub opponent_stats: Account<'info, UserStats>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(seeds = [b"season"], bump = season.bump)]
+    pub season: Account<'info, Season>,
     pub system_program: Program<'info, System>
 }
\ No newline at end of file
@@ -9,7 +9,11 @@ pub fn create_user_stats(ctx: Context<CreateUserStats>, name: String) -> Result<
     }
     let bump = *ctx.bumps.get("user_stats").unwrap();
     ctx.accounts.user_stats.initialize(name, bump);
-    ctx.accounts.leaderboard.add_new_player(ctx.accounts.user.key())
+
+    let user_stats = &ctx.accounts.user_stats;
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.update_entry(ctx.accounts.user.key(), user_stats.get_name(), user_stats.get_score());
+    Ok(())
 }
 
 
@@ -22,9 +26,7 @@ pub struct CreateUserStats<'info> {
         payer = user,
         space = 8 + UserStats::MAX_ACC_SIZE, seeds = [b"user-stats", user.key().as_ref()], bump)]
     pub user_stats: Account<'info, UserStats>,
-    #[account(
-        mut,
-        seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.bump)]
     pub leaderboard: Account<'info, Leaderboard>,
     pub system_program: Program<'info, System>
 }
@@ -1,41 +1,37 @@
 use crate::state::user::*;
 use crate::state::game::*;
-use crate::state::leaderboard::*;
+use crate::state::deck::*;
+use crate::state::card_registry::*;
 use crate::errors::CardGameError;
 use anchor_lang::prelude::*;
 
-pub fn setup_game(ctx: Context<SetupGame>, p1_hand: Vec<Card>, p2_hand: Vec<Card>) -> Result<()> {
+pub fn create_challenge(ctx: Context<CreateChallenge>, turn_limit: i64, commitment: [u8; 32]) -> Result<()> {
     match ctx.accounts{
-        SetupGame {
+        CreateChallenge {
             game,
             p1,
             p1_stats,
             p2,
             p2_stats,
-            leaderboard,
+            p1_deck,
+            card_registry,
             system_program: _,
         } => {
 
-            if !leaderboard.check_if_created(p1.key()) {
-                return Err(CardGameError::UserStatsAccountMissing.into())
-            }
-
+            // Both players are known to be registered, their stats accounts only deserialize if they exist
             if p1_stats.check_active_game().is_some() {
                 return Err(CardGameError::YouHaveAnActiveGame.into())
             }
-        
-            if !leaderboard.check_if_created(p2.key()) {
-                return Err(CardGameError::OpposingStatsAccountMissing.into())
-            }
-            
+
             if p2_stats.check_active_game().is_some() {
                 return Err(CardGameError::OpponentHasAnActiveGame.into())
             }
         
+            // p2 is only tied to the game once they accept
             p1_stats.set_active_game(game.key());
-            p2_stats.set_active_game(game.key());
-            
-            game.start([p1.key(), p2.key()], p1_hand, p2_hand)
+
+            let p1_cards = p1_deck.resolve(card_registry)?;
+            game.create_challenge([p1.key(), p2.key()], p1_cards, turn_limit, commitment)
         }
     }
 
@@ -43,24 +39,27 @@ pub fn setup_game(ctx: Context<SetupGame>, p1_hand: Vec<Card>, p2_hand: Vec<Card
 
 
 #[derive(Accounts)]
-pub struct SetupGame<'info> {
+pub struct CreateChallenge<'info> {
     #[account(init, payer = p1, space = 8 + Game::MAXIMUM_SIZE)]
     pub game: Account<'info, Game>,
     #[account(mut)]
     pub p1: Signer<'info>,
     #[account(
         mut, 
-        seeds=[b"user-stats", p1.key().as_ref()], bump
+        seeds=[b"user-stats", p1.key().as_ref()], bump = p1_stats.bump
     )]
     pub p1_stats: Account<'info, UserStats>,
-    #[account(mut)]
     /// CHECK: Only taking p2 pubkey to check that p2_stats belongs to the same player
     pub p2: UncheckedAccount<'info>,
     #[account(
-        mut, 
-        seeds=[b"user-stats", p2.key().as_ref()], bump
+        seeds=[b"user-stats", p2.key().as_ref()], bump = p2_stats.bump
     )]
     pub p2_stats: Account<'info, UserStats>,
-    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(
+        seeds=[b"deck", p1.key().as_ref(), &[p1_deck.index]], bump = p1_deck.bump
+    )]
+    pub p1_deck: Account<'info, Deck>,
+    #[account(seeds = [b"card-registry"], bump = card_registry.bump)]
+    pub card_registry: Account<'info, CardRegistry>,
     pub system_program: Program<'info, System>
 }
\ No newline at end of file
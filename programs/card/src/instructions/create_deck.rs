@@ -0,0 +1,25 @@
+use crate::state::deck::*;
+use crate::state::card_registry::*;
+use anchor_lang::prelude::*;
+
+pub fn create_deck(ctx: Context<CreateDeck>, index: u8, cards: Vec<u16>) -> Result<()> {
+    let bump = *ctx.bumps.get("deck").unwrap();
+    let owner = ctx.accounts.owner.key();
+    ctx.accounts.deck.initialize(owner, index, bump, cards, &ctx.accounts.card_registry)
+}
+
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct CreateDeck<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Deck::MAX_ACC_SIZE, seeds = [b"deck", owner.key().as_ref(), &[index]], bump)]
+    pub deck: Account<'info, Deck>,
+    #[account(seeds = [b"card-registry"], bump = card_registry.bump)]
+    pub card_registry: Account<'info, CardRegistry>,
+    pub system_program: Program<'info, System>
+}
@@ -0,0 +1,52 @@
+use crate::state::user::*;
+use crate::state::game::*;
+use crate::errors::CardGameError;
+use anchor_lang::prelude::*;
+
+// Player 2 turns down a challenge, player 1 gets the game account rent back
+pub fn decline_challenge(ctx: Context<DeclineChallenge>) -> Result<()> {
+    match ctx.accounts{
+        DeclineChallenge {
+            game,
+            p1,
+            p1_stats,
+            p2,
+            p2_stats,
+        } => {
+
+            if !game.is_pending() {
+                return Err(CardGameError::ChallengeNotPending.into())
+            }
+
+            if game.get_players() != [p1.key(), p2.key()] {
+                return Err(CardGameError::MismatchPlayerKeys.into())
+            }
+
+            p1_stats.clear_active_if(game.key());
+            p2_stats.clear_active_if(game.key());
+            Ok(())
+        }
+    }
+
+}
+
+
+#[derive(Accounts)]
+pub struct DeclineChallenge<'info> {
+    #[account(mut, close = p1)]
+    pub game: Account<'info, Game>,
+    #[account(mut)]
+    /// CHECK: Only taking p1 pubkey to check that p1_stats belongs to the same player and to refund rent
+    pub p1: UncheckedAccount<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", p1.key().as_ref()], bump
+    )]
+    pub p1_stats: Account<'info, UserStats>,
+    pub p2: Signer<'info>,
+    #[account(
+        mut, 
+        seeds=[b"user-stats", p2.key().as_ref()], bump
+    )]
+    pub p2_stats: Account<'info, UserStats>,
+}
\ No newline at end of file
@@ -2,7 +2,8 @@ use crate::errors::CardGameError;
 use crate::state::game::*;
 use anchor_lang::prelude::*;
 
-pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
+// link is the next one down the player's seed chain
+pub fn end_turn(ctx: Context<EndTurn>, link: [u8; 32]) -> Result<()> {
     let game = &mut ctx.accounts.game;
 
     require_keys_eq!(
@@ -11,7 +12,7 @@ pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
         CardGameError::NotPlayersTurn
     );
 
-    game.end_turn()
+    game.end_turn(link, Clock::get()?.unix_timestamp)
 }
 
 #[derive(Accounts)]
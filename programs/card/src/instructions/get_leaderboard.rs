@@ -0,0 +1,14 @@
+use crate::state::leaderboard::*;
+use anchor_lang::prelude::*;
+
+// Read only, meant to be simulated by clients to page through the ranking
+pub fn get_leaderboard(ctx: Context<GetLeaderboard>, offset: u32, limit: u8) -> Result<Vec<LeaderboardEntry>> {
+    Ok(ctx.accounts.leaderboard.get_page(offset, limit))
+}
+
+
+#[derive(Accounts)]
+pub struct GetLeaderboard<'info> {
+    #[account(seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use crate::errors::CardGameError;
+use crate::state::card_registry::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+pub fn init_card_registry(ctx: Context<InitCardRegistry>) -> Result<()> {
+    let bump = *ctx.bumps.get("card_registry").unwrap();
+    let admin = ctx.accounts.admin.key();
+    ctx.accounts.card_registry.initialize(admin, bump);
+    Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct InitCardRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    // The registry is a single global account, only the program's upgrade authority gets to open it
+    // The program data account of an upgradeable program always sits at this address
+    #[account(
+        seeds = [crate::ID.as_ref()], bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ CardGameError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CardRegistry::MAX_ACC_SIZE, seeds = [b"card-registry"], bump)]
+    pub card_registry: Account<'info, CardRegistry>,
+    pub system_program: Program<'info, System>
+}
@@ -13,7 +13,7 @@ pub struct InitLeaderboard<'info> {
     #[account(
         init,
         payer = user,
-        space = 9000, seeds = [b"leaderboard".as_ref()], bump)]
+        space = 8 + Leaderboard::MAX_ACC_SIZE, seeds = [b"leaderboard".as_ref()], bump)]
     pub leaderboard: Account<'info, Leaderboard>,
     pub system_program: Program<'info, System>
 }
\ No newline at end of file
@@ -0,0 +1,29 @@
+use crate::errors::CardGameError;
+use crate::state::season::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+pub fn init_season(ctx: Context<InitSeason>, duration: i64) -> Result<()> {
+    let bump = *ctx.bumps.get("season").unwrap();
+    let admin = ctx.accounts.admin.key();
+    ctx.accounts.season.initialize(admin, bump, duration, Clock::get()?.unix_timestamp)
+}
+
+
+#[derive(Accounts)]
+pub struct InitSeason<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    // Same as the card registry, the upgrade authority opens the one season account and runs it from then on
+    #[account(
+        seeds = [crate::ID.as_ref()], bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ CardGameError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Season::MAX_ACC_SIZE, seeds = [b"season"], bump)]
+    pub season: Account<'info, Season>,
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::errors::CardGameError;
+use crate::state::leaderboard::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::Discriminator;
+
+// Rewrites the leaderboard made before the top 50 into the current layout and hands the spare rent to the admin
+// Entries start out empty, migrate_user_stats puts every player back on it
+pub fn migrate_leaderboard(ctx: Context<MigrateLeaderboard>) -> Result<()> {
+    let leaderboard = ctx.accounts.leaderboard.to_account_info();
+    let new_len = 8 + Leaderboard::MAX_ACC_SIZE;
+    // The old account was a fixed 9000 bytes, well past what the top 50 needs
+    if leaderboard.data_len() <= new_len {
+        return Err(CardGameError::AccountAlreadyMigrated.into())
+    }
+
+    let legacy = {
+        let data = leaderboard.try_borrow_data()?;
+        if data.get(..8) != Some(&Leaderboard::DISCRIMINATOR[..]) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into())
+        }
+        LegacyLeaderboard::deserialize(&mut &data[8..])?
+    };
+
+    leaderboard.realloc(new_len, false)?;
+    let migrated = Leaderboard { bump: legacy.bump, entries: Vec::new() };
+    migrated.try_serialize(&mut &mut leaderboard.try_borrow_mut_data()?[..])?;
+
+    let admin = ctx.accounts.admin.to_account_info();
+    let spare = leaderboard.lamports().saturating_sub(Rent::get()?.minimum_balance(new_len));
+    **leaderboard.try_borrow_mut_lamports()? -= spare;
+    **admin.try_borrow_mut_lamports()? += spare;
+    Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct MigrateLeaderboard<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    // Same as init_season, only the upgrade authority that shipped the new layout can rewrite the old account
+    #[account(
+        seeds = [crate::ID.as_ref()], bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ CardGameError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    /// CHECK: Still in the old layout that Leaderboard can't read, the instruction checks and parses it itself
+    #[account(
+        mut,
+        seeds = [b"leaderboard"], bump)]
+    pub leaderboard: UncheckedAccount<'info>,
+}
\ No newline at end of file
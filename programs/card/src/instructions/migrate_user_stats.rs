@@ -0,0 +1,56 @@
+use crate::errors::CardGameError;
+use crate::state::user::*;
+use crate::state::leaderboard::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+// Rewrites a user stats account made before ratings into the current layout, anyone can pay for it
+pub fn migrate_user_stats(ctx: Context<MigrateUserStats>) -> Result<()> {
+    let user_stats = ctx.accounts.user_stats.to_account_info();
+    let new_len = 8 + UserStats::MAX_ACC_SIZE;
+    // Accounts made before ratings were allocated for the old layout, and are all smaller than that
+    if user_stats.data_len() >= new_len {
+        return Err(CardGameError::AccountAlreadyMigrated.into())
+    }
+
+    let legacy = {
+        let data = user_stats.try_borrow_data()?;
+        if data.get(..8) != Some(&UserStats::DISCRIMINATOR[..]) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into())
+        }
+        LegacyUserStats::deserialize(&mut &data[8..])?
+    };
+
+    let rent = Rent::get()?.minimum_balance(new_len);
+    if rent > user_stats.lamports() {
+        let top_up = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: user_stats.clone(),
+        };
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), top_up), rent - user_stats.lamports())?;
+    }
+    user_stats.realloc(new_len, false)?;
+
+    let stats = UserStats::from_legacy(legacy);
+    stats.try_serialize(&mut &mut user_stats.try_borrow_mut_data()?[..])?;
+    ctx.accounts.leaderboard.update_entry(ctx.accounts.player.key(), stats.get_name(), stats.get_score());
+    Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct MigrateUserStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Only taking the player's pubkey to find their user stats
+    pub player: UncheckedAccount<'info>,
+    /// CHECK: Still in the old layout that UserStats can't read, the instruction checks and parses it itself
+    #[account(
+        mut,
+        seeds = [b"user-stats", player.key().as_ref()], bump)]
+    pub user_stats: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
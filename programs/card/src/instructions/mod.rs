@@ -1,17 +1,51 @@
 pub use play_card::*;
-pub use setup_game::*;
+pub use create_challenge::*;
+pub use accept_challenge::*;
+pub use reveal_seed::*;
+pub use cancel_challenge::*;
+pub use decline_challenge::*;
 pub use end_turn::*;
 pub use attack::*;
 pub use create_acc::*;
+pub use migrate_user_stats::*;
 pub use init_leaderboard::*;
+pub use migrate_leaderboard::*;
+pub use get_leaderboard::*;
+pub use init_season::*;
+pub use rollover_season::*;
 pub use set_scores::*;
 pub use concede::*;
+pub use claim_timeout::*;
+pub use close_game::*;
+pub use cast_spell::*;
+pub use create_deck::*;
+pub use update_deck::*;
+pub use init_card_registry::*;
+pub use add_card::*;
+pub use update_card::*;
 
 pub mod play_card;
-pub mod setup_game;
+pub mod create_challenge;
+pub mod accept_challenge;
+pub mod reveal_seed;
+pub mod cancel_challenge;
+pub mod decline_challenge;
 pub mod end_turn;
 pub mod attack;
 pub mod create_acc;
+pub mod migrate_user_stats;
 pub mod init_leaderboard;
+pub mod migrate_leaderboard;
+pub mod get_leaderboard;
+pub mod init_season;
+pub mod rollover_season;
 pub mod set_scores;
-pub mod concede;
\ No newline at end of file
+pub mod concede;
+pub mod claim_timeout;
+pub mod close_game;
+pub mod cast_spell;
+pub mod create_deck;
+pub mod update_deck;
+pub mod init_card_registry;
+pub mod add_card;
+pub mod update_card;
\ No newline at end of file
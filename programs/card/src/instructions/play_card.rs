@@ -2,7 +2,7 @@ use crate::errors::CardGameError;
 use crate::state::game::*;
 use anchor_lang::prelude::*;
 
-pub fn play_card(ctx: Context<PlayCard>, pos: u8, card_index: u8) -> Result<()> {
+pub fn play_card(ctx: Context<PlayCard>, pos: u8, card_index: u8, target: Option<EffectTarget>) -> Result<()> {
     let game = &mut ctx.accounts.game;
 
     require_keys_eq!(
@@ -11,7 +11,7 @@ pub fn play_card(ctx: Context<PlayCard>, pos: u8, card_index: u8) -> Result<()>
         CardGameError::NotPlayersTurn
     );
 
-    game.play_card(pos, card_index)
+    game.play_card(pos, card_index, target)
 }
 
 #[derive(Accounts)]
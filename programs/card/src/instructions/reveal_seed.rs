@@ -0,0 +1,15 @@
+use crate::state::game::*;
+use anchor_lang::prelude::*;
+
+// Reveals the next link of the player's seed chain, dealing the opening hands or the player's next turn
+pub fn reveal_seed(ctx: Context<RevealSeed>, link: [u8; 32]) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.reveal_seed(ctx.accounts.player.key(), link, Clock::get()?.unix_timestamp)
+}
+
+#[derive(Accounts)]
+pub struct RevealSeed<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+}
\ No newline at end of file
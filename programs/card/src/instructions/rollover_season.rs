@@ -0,0 +1,34 @@
+use crate::errors::CardGameError;
+use crate::state::season::*;
+use crate::state::leaderboard::*;
+use anchor_lang::prelude::*;
+
+pub fn rollover_season(ctx: Context<RolloverSeason>) -> Result<()> {
+    let bump = *ctx.bumps.get("season_archive").unwrap();
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = ctx.accounts;
+    accounts.season.rollover(&mut accounts.season_archive, bump, &mut accounts.leaderboard, now)
+}
+
+
+#[derive(Accounts)]
+pub struct RolloverSeason<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ CardGameError::NotSeasonAdmin,
+        seeds = [b"season"], bump = season.bump)]
+    pub season: Account<'info, Season>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + SeasonArchive::MAX_ACC_SIZE,
+        seeds = [b"season-archive", season.number.to_le_bytes().as_ref()], bump)]
+    pub season_archive: Account<'info, SeasonArchive>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use crate::errors::CardGameError;
 use crate::state::user::*;
 use crate::state::game::*;
+use crate::state::leaderboard::*;
+use crate::state::season::*;
 use anchor_lang::prelude::*;
 
 pub fn set_score(ctx: Context<SetScores>) -> Result<()> {
@@ -11,14 +13,32 @@ pub fn set_score(ctx: Context<SetScores>) -> Result<()> {
                 p2,
                 p2_stats,
                 game,
+                leaderboard,
+                season,
                 system_program: _,
             } => {
                 let p1_key = p1.key();
                 let p2_key = p2.key();
+
+                // Settling first so a replay is reported as such rather than as a cleared active game
+                game.settle()?;
+
+                // Both players have to still be tied to this exact game
+                let game_key = game.key();
+                if p1_stats.check_active_game() != Some(game_key)
+                    || p2_stats.check_active_game() != Some(game_key) {
+                    return Err(CardGameError::NotPlayersActiveGame.into())
+                }
+                p1_stats.sync_season(season.number);
+                p2_stats.sync_season(season.number);
+
                 match game.get_game_state() {
-                    GameState::Active => Err(CardGameError::GameStillActive.into()),
+                    GameState::Active | GameState::Pending | GameState::Revealing => Err(CardGameError::GameStillActive.into()),
                     GameState::Tie => {
                         if game.match_pubkeys([p1_key, p2_key]) {
+                            UserStats::record_tie(p1_stats, p2_stats);
+                            leaderboard.update_entry(p1_key, p1_stats.get_name(), p1_stats.get_score());
+                            leaderboard.update_entry(p2_key, p2_stats.get_name(), p2_stats.get_score());
                             p1_stats.clear_active();
                             p2_stats.clear_active();
                             Ok(())
@@ -31,15 +51,17 @@ pub fn set_score(ctx: Context<SetScores>) -> Result<()> {
                             return Err(CardGameError::MismatchPlayerKeys.into())
                         }
                         if winner == p1_key {
-                            p1_stats.increase_score();
+                            UserStats::record_win(p1_stats, p2_stats);
+                            leaderboard.update_entry(p1_key, p1_stats.get_name(), p1_stats.get_score());
+                            leaderboard.update_entry(p2_key, p2_stats.get_name(), p2_stats.get_score());
                             p1_stats.clear_active();
-                            p2_stats.decrease_score();
                             p2_stats.clear_active();
                             Ok(())
                         } else {
-                            p2_stats.increase_score();
+                            UserStats::record_win(p2_stats, p1_stats);
+                            leaderboard.update_entry(p2_key, p2_stats.get_name(), p2_stats.get_score());
+                            leaderboard.update_entry(p1_key, p1_stats.get_name(), p1_stats.get_score());
                             p2_stats.clear_active();
-                            p1_stats.decrease_score();
                             p1_stats.clear_active();
                             Ok(())
                             
@@ -67,6 +89,13 @@ pub struct SetScores<'info> {
         mut,
         seeds = [b"user-stats", p2.key().as_ref()], bump=p2_stats.bump)]
     pub p2_stats: Account<'info, UserStats>,
+    #[account(mut)]
     pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard"], bump = leaderboard.bump)]
+    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(seeds = [b"season"], bump = season.bump)]
+    pub season: Account<'info, Season>,
     pub system_program: Program<'info, System>
 }
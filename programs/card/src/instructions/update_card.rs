@@ -0,0 +1,19 @@
+use crate::errors::CardGameError;
+use crate::state::card_registry::*;
+use crate::state::game::*;
+use anchor_lang::prelude::*;
+
+pub fn update_card(ctx: Context<UpdateCard>, id: u16, card: Card) -> Result<()> {
+    ctx.accounts.card_registry.update_card(id, card)
+}
+
+
+#[derive(Accounts)]
+pub struct UpdateCard<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ CardGameError::NotRegistryAdmin,
+        seeds = [b"card-registry"], bump = card_registry.bump)]
+    pub card_registry: Account<'info, CardRegistry>,
+}
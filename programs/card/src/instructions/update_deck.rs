@@ -0,0 +1,19 @@
+use crate::state::deck::*;
+use crate::state::card_registry::*;
+use anchor_lang::prelude::*;
+
+pub fn update_deck(ctx: Context<UpdateDeck>, cards: Vec<u16>) -> Result<()> {
+    ctx.accounts.deck.set_cards(cards, &ctx.accounts.card_registry)
+}
+
+
+#[derive(Accounts)]
+pub struct UpdateDeck<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"deck", owner.key().as_ref(), &[deck.index]], bump = deck.bump)]
+    pub deck: Account<'info, Deck>,
+    #[account(seeds = [b"card-registry"], bump = card_registry.bump)]
+    pub card_registry: Account<'info, CardRegistry>,
+}
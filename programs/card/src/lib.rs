@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use instructions::*;
 use state::game::*;
+use state::leaderboard::*;
 
 pub mod errors;
 pub mod instructions;
@@ -12,30 +13,90 @@ declare_id!("ACYSdixSJLAewjEtKG17p4FsHVoEj1ptpB1UNv7vh4EJ");
 pub mod card {
     use super::*;
 
-    pub fn setup_game(ctx: Context<SetupGame>, p1_hand: Vec<Card>, p2_hand: Vec<Card>) -> Result<()> {
-        instructions::setup_game::setup_game(ctx, p1_hand, p2_hand)
+    pub fn create_challenge(ctx: Context<CreateChallenge>, turn_limit: i64, commitment: [u8; 32]) -> Result<()> {
+        instructions::create_challenge::create_challenge(ctx, turn_limit, commitment)
     }
 
-    pub fn play_card(ctx: Context<PlayCard>, pos: u8, card_index: u8) -> Result<()> {
-        instructions::play_card::play_card(ctx, pos, card_index)
+    pub fn accept_challenge(ctx: Context<AcceptChallenge>, commitment: [u8; 32]) -> Result<()> {
+        instructions::accept_challenge::accept_challenge(ctx, commitment)
     }
 
-    pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
-        instructions::end_turn::end_turn(ctx)
+    pub fn reveal_seed(ctx: Context<RevealSeed>, link: [u8; 32]) -> Result<()> {
+        instructions::reveal_seed::reveal_seed(ctx, link)
+    }
+
+    pub fn cancel_challenge(ctx: Context<CancelChallenge>) -> Result<()> {
+        instructions::cancel_challenge::cancel_challenge(ctx)
+    }
+
+    pub fn decline_challenge(ctx: Context<DeclineChallenge>) -> Result<()> {
+        instructions::decline_challenge::decline_challenge(ctx)
+    }
+
+    pub fn play_card(ctx: Context<PlayCard>, pos: u8, card_index: u8, target: Option<EffectTarget>) -> Result<()> {
+        instructions::play_card::play_card(ctx, pos, card_index, target)
+    }
+
+    pub fn cast_spell(ctx: Context<CastSpell>, card_index: u8, target_row: u8, target_pos: u8) -> Result<()> {
+        instructions::cast_spell::cast_spell(ctx, card_index, target_row, target_pos)
+    }
+
+    pub fn end_turn(ctx: Context<EndTurn>, link: [u8; 32]) -> Result<()> {
+        instructions::end_turn::end_turn(ctx, link)
     }
     
-    pub fn attack(ctx: Context<Attack>, ally_pos: u8, enemy_pos: u8) -> Result<()> {
-        instructions::attack::attack(ctx, ally_pos, enemy_pos)
+    pub fn attack(ctx: Context<Attack>, ally_pos: u8, target: AttackTarget) -> Result<()> {
+        instructions::attack::attack(ctx, ally_pos, target)
     }
 
     pub fn init_leaderboard(ctx: Context<InitLeaderboard>, bump: u8) -> Result<()> {
         instructions::init_leaderboard::init_leaderboard(ctx, bump)
     }
 
+    pub fn migrate_leaderboard(ctx: Context<MigrateLeaderboard>) -> Result<()> {
+        instructions::migrate_leaderboard::migrate_leaderboard(ctx)
+    }
+
+    pub fn get_leaderboard(ctx: Context<GetLeaderboard>, offset: u32, limit: u8) -> Result<Vec<LeaderboardEntry>> {
+        instructions::get_leaderboard::get_leaderboard(ctx, offset, limit)
+    }
+
+    pub fn init_season(ctx: Context<InitSeason>, duration: i64) -> Result<()> {
+        instructions::init_season::init_season(ctx, duration)
+    }
+
+    pub fn rollover_season(ctx: Context<RolloverSeason>) -> Result<()> {
+        instructions::rollover_season::rollover_season(ctx)
+    }
+
     pub fn create_user_stats(ctx: Context<CreateUserStats>, name: String) -> Result<()> {
         instructions::create_acc::create_user_stats(ctx, name)
     }
 
+    pub fn migrate_user_stats(ctx: Context<MigrateUserStats>) -> Result<()> {
+        instructions::migrate_user_stats::migrate_user_stats(ctx)
+    }
+
+    pub fn init_card_registry(ctx: Context<InitCardRegistry>) -> Result<()> {
+        instructions::init_card_registry::init_card_registry(ctx)
+    }
+
+    pub fn add_card(ctx: Context<AddCard>, card: Card) -> Result<()> {
+        instructions::add_card::add_card(ctx, card)
+    }
+
+    pub fn update_card(ctx: Context<UpdateCard>, id: u16, card: Card) -> Result<()> {
+        instructions::update_card::update_card(ctx, id, card)
+    }
+
+    pub fn create_deck(ctx: Context<CreateDeck>, index: u8, cards: Vec<u16>) -> Result<()> {
+        instructions::create_deck::create_deck(ctx, index, cards)
+    }
+
+    pub fn update_deck(ctx: Context<UpdateDeck>, cards: Vec<u16>) -> Result<()> {
+        instructions::update_deck::update_deck(ctx, cards)
+    }
+
     pub fn set_scores(ctx: Context<SetScores>) -> Result<()> {
         instructions::set_scores::set_score(ctx)
     }
@@ -43,6 +104,14 @@ pub mod card {
     pub fn concede(ctx: Context<Concede>) -> Result<()> {
         instructions::concede::concede(ctx)
     }
+
+    pub fn claim_timeout(ctx: Context<ClaimTimeout>) -> Result<()> {
+        instructions::claim_timeout::claim_timeout(ctx)
+    }
+
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        instructions::close_game::close_game(ctx)
+    }
 }
 
 
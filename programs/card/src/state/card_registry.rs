@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::errors::CardGameError;
+use crate::state::game::*;
+
+// Authoritative card definitions, a card's id is its index in cards
+#[account]
+pub struct CardRegistry {
+    pub admin: Pubkey, // 32
+    pub bump: u8, // 1
+    cards: Vec<Card>, // 4 + 200 * Card::SIZE
+}
+
+impl CardRegistry {
+    pub const MAX_CARDS: usize = 200;
+    pub const MAX_ACC_SIZE: usize = 32 + 1 + (4 + Self::MAX_CARDS * Card::SIZE);
+
+    pub fn initialize(&mut self, admin: Pubkey, bump: u8) {
+        self.admin = admin;
+        self.bump = bump;
+        self.cards = Vec::new();
+    }
+
+    pub fn add_card(&mut self, card: Card) -> Result<()> {
+        if self.cards.len() >= Self::MAX_CARDS {
+            return Err(CardGameError::CardRegistryFull.into())
+        }
+
+        self.cards.push(Self::definition(card)?);
+        Ok(())
+    }
+
+    pub fn update_card(&mut self, id: u16, card: Card) -> Result<()> {
+        let definition = Self::definition(card)?;
+        match self.cards.get_mut(id as usize) {
+            Some(existing) => *existing = definition,
+            None => return Err(CardGameError::UnknownCardId.into()),
+        }
+        Ok(())
+    }
+
+    pub fn get_card(&self, id: u16) -> Option<Card> {
+        self.cards.get(id as usize).copied()
+    }
+
+    // Strips in-game state so a definition always starts fresh in hand
+    fn definition(card: Card) -> Result<Card> {
+        if !(0..=10).contains(&card.mana) || (!card.is_spell() && card.hp <= 0) {
+            return Err(CardGameError::InvalidCardDefinition.into())
+        }
+
+        Ok(Card {
+            max_hp: card.hp,
+            moves: 0,
+            summoning_sick: false,
+            aura_atk: 0,
+            aura_hp: 0,
+            ..card
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::errors::CardGameError;
+use crate::state::card_registry::CardRegistry;
+use crate::state::game::Card;
+
+#[account]
+pub struct Deck {
+    pub owner: Pubkey, // 32
+    pub index: u8, // 1
+    cards: Vec<u16>, // 4 + 30 * 2
+    pub bump: u8, // 1
+}
+
+impl Deck {
+    pub const MAX_CARDS: usize = 30;
+    pub const MAX_ACC_SIZE: usize = 32 + 1 + (4 + Self::MAX_CARDS * 2) + 1;
+
+    pub fn initialize(&mut self, owner: Pubkey, index: u8, bump: u8, cards: Vec<u16>, registry: &CardRegistry) -> Result<()> {
+        self.owner = owner;
+        self.index = index;
+        self.bump = bump;
+        self.set_cards(cards, registry)
+    }
+
+    pub fn set_cards(&mut self, cards: Vec<u16>, registry: &CardRegistry) -> Result<()> {
+        if cards.len() > Self::MAX_CARDS {
+            return Err(CardGameError::DeckTooLarge.into())
+        }
+        if cards.iter().any(|id| registry.get_card(*id).is_none()) {
+            return Err(CardGameError::UnknownCardId.into())
+        }
+
+        self.cards = cards;
+        Ok(())
+    }
+
+    // Card stats for every id in the deck, ready to be shuffled into a game
+    pub fn resolve(&self, registry: &CardRegistry) -> Result<Vec<Card>> {
+        self.cards
+            .iter()
+            .map(|id| registry.get_card(*id).ok_or_else(|| CardGameError::UnknownCardId.into()))
+            .collect()
+    }
+}
\ No newline at end of file
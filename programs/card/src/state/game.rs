@@ -1,287 +1,355 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use crate::errors::CardGameError;
+use crate::state::deck::Deck;
+use card_engine as engine;
 
 
 #[account] // Requires certain functions (e.g. (de)serialize T) and sets owner of data to ID 
 pub struct Game {
     players: [Pubkey; 2], // 32 * 2
     turn: u8, // 1
-    board: [[Option<Card>; 7]; 2], // 14 * (1 + 4) = 70
+    board: [[Option<Card>; 7]; 2], // 14 * (1 + Card::SIZE)
     state: GameState, // 32 + 1
     health: [i8; 2], // 2
     mana: [i8; 2], // 2
-    p1_hand: Vec<Card>, // 10 * 4
-    p2_hand: Vec<Card>, // 10 * 4
+    p1_hand: Vec<Card>, // 4 + 10 * Card::SIZE
+    p2_hand: Vec<Card>, // 4 + 10 * Card::SIZE
+    seed: u64, // 8
+    p1_deck: Vec<Card>, // 4 + 30 * Card::SIZE
+    p2_deck: Vec<Card>, // 4 + 30 * Card::SIZE
+    settled: bool, // 1
+    turn_limit: i64, // 8
+    turn_started_at: i64, // 8
+    commitments: [[u8; 32]; 2], // 32 * 2, last link each player revealed, their commitment before the first one
+    reveals: [Option<[u8; 32]>; 2], // (1 + 32) * 2, links revealed for the turn about to be dealt
 }
 
-// Max index of cards on each row
-const MAX_ROW: u8 = 6;
+// Bounds in seconds for how long a player can take before their turn can be claimed
+pub const MIN_TURN_LIMIT: i64 = 30;
+pub const MAX_TURN_LIMIT: i64 = 24 * 60 * 60;
 
+// Links each player reveals over a game, one for the opening hands and one for every turn after it
+pub const SEED_CHAIN_LENGTH: usize = 1 + engine::MAX_TURN as usize;
+
+// One step down a player's hash chain, tied to their key so the opponent can't copy it
+// Every link revealed has to hash to the one before it, the first to the commitment
+pub fn seed_commitment(player: &Pubkey, link: &[u8; 32]) -> [u8; 32] {
+    hashv(&[player.as_ref(), link]).to_bytes()
+}
+
+// Whole chain grown from a secret, the commitment first and then the links in the order they are revealed
+// Only the secret has to be kept, every link can be worked out again from it
+pub fn seed_chain(player: &Pubkey, secret: &[u8; 32]) -> Vec<[u8; 32]> {
+    let mut chain = vec![*secret; SEED_CHAIN_LENGTH + 1];
+    for i in (0..SEED_CHAIN_LENGTH).rev() {
+        chain[i] = seed_commitment(player, &chain[i + 1]);
+    }
+    chain
+}
+
+// Seed for the cards drawn and random targets picked once turn is over, turn 0 being the opening hands
+// Neither player knows the other's next link, so nobody can work the seed out before both are revealed
+pub fn combine_seeds(seed: u64, turn: u8, p1_link: &[u8; 32], p2_link: &[u8; 32]) -> u64 {
+    let hash = hashv(&[&seed.to_le_bytes(), &[turn], p1_link, p2_link]);
+    u64::from_le_bytes(hash.to_bytes()[..8].try_into().unwrap())
+}
+
+// The rules live in the card_engine crate, this account holds a copy of the engine's state
+// along with what only matters on chain (players' keys, settlement and the turn timer)
 impl Game {
-    pub const MAXIMUM_SIZE: usize = (32 * 2) + 1 + (14 * (1 + 4)) + (32 + 1) +
-    2 + 2 + (10 * 4) + (10 * 4);
+    pub const MAXIMUM_SIZE: usize = (32 * 2) + 1 + (14 * (1 + Card::SIZE)) + (32 + 1) +
+    2 + 2 + (4 + engine::MAX_HAND * Card::SIZE) * 2 + 8 + (4 + Deck::MAX_CARDS * Card::SIZE) * 2 + 1 + 8 + 8 +
+    (32 * 2) + (1 + 32) * 2;
 
-    pub fn start(&mut self, players: [Pubkey; 2], p1_hand: Vec<Card>, p2_hand: Vec<Card>) -> Result<()> {
+    // Player 1 opens the game with their deck and the head of their seed chain, nothing can be played until player 2 accepts
+    pub fn create_challenge(&mut self, players: [Pubkey; 2], p1_deck: Vec<Card>, turn_limit: i64, commitment: [u8; 32]) -> Result<()> {
         require_eq!(self.turn, 0, CardGameError::GameAlreadyStarted);
+        require!(
+            (MIN_TURN_LIMIT..=MAX_TURN_LIMIT).contains(&turn_limit),
+            CardGameError::InvalidTurnLimit
+        );
         self.players = players;
-        self.turn = 1;
-        self.health = [30, 30];
-        self.mana = [1, 1];
-        self.p1_hand = p1_hand;
-        self.p2_hand = p2_hand;
-        // self.p1_hand.push(Card{hp:10, atk:10, mana:1, moves:0});
-        // self.p2_hand.push(Card{hp:15, atk:7, mana:1, moves:0});
+        self.turn_limit = turn_limit;
+        self.p1_deck = p1_deck;
+        self.commitments[0] = commitment;
+        self.state = GameState::Pending;
         Ok(())
     }
 
-    pub fn is_active(&self) -> bool {
-        self.state == GameState::Active
+    // Player 2 commits to their own seed chain, both players then have turn_limit to reveal its first link
+    pub fn accept_challenge(&mut self, p2_deck: Vec<Card>, commitment: [u8; 32], now: i64) -> Result<()> {
+        require!(self.is_pending(), CardGameError::ChallengeNotPending);
+        self.p2_deck = p2_deck;
+        self.commitments[1] = commitment;
+        self.state = GameState::Revealing;
+        self.turn_started_at = now;
+        Ok(())
     }
 
-    fn current_player_index(&self) -> usize {
-        ((self.turn - 1) % 2) as usize
+    // Before the game either player can reveal first, the second reveal deals the opening hands
+    // Mid game only the player going next reveals, once the player on turn ended it, and that deals their turn
+    // Whoever reveals second can work out what is dealt beforehand, but the link is fixed by the chain
+    // so holding it back only loses on the turn limit
+    pub fn reveal_seed(&mut self, player: Pubkey, link: [u8; 32], now: i64) -> Result<()> {
+        let index = match self.players.iter().position(|key| *key == player) {
+            Some(index) => index,
+            None => return Err(CardGameError::MismatchPlayerKeys.into()),
+        };
+
+        match self.state {
+            GameState::Revealing => {
+                self.reveal_link(index, link)?;
+                if let [Some(p1_link), Some(p2_link)] = self.reveals {
+                    let decks = [to_engine_cards(&self.p1_deck), to_engine_cards(&self.p2_deck)];
+                    self.load_engine(engine::Game::new(decks, combine_seeds(0, 0, &p1_link, &p2_link)));
+                    self.advance_chains(p1_link, p2_link);
+                    self.turn_started_at = now;
+                }
+                Ok(())
+            }
+            GameState::Active => {
+                require!(self.reveals[(index + 1) % 2].is_some(), CardGameError::NotRevealingSeeds);
+                self.reveal_link(index, link)?;
+                if let [Some(p1_link), Some(p2_link)] = self.reveals {
+                    self.seed = combine_seeds(self.seed, self.turn, &p1_link, &p2_link);
+                    self.advance_chains(p1_link, p2_link);
+                    self.run(|game| game.end_turn())?;
+                    self.turn_started_at = now;
+                }
+                Ok(())
+            }
+            _ => Err(CardGameError::NotRevealingSeeds.into()),
+        }
     }
 
-    pub fn current_player(&self) -> Pubkey {
-        self.players[self.current_player_index()]
+    // Each link has to hash to the last one the player revealed
+    fn reveal_link(&mut self, index: usize, link: [u8; 32]) -> Result<()> {
+        require!(self.reveals[index].is_none(), CardGameError::SeedAlreadyRevealed);
+        require!(
+            seed_commitment(&self.players[index], &link) == self.commitments[index],
+            CardGameError::InvalidSeedReveal
+        );
+        self.reveals[index] = Some(link);
+        Ok(())
     }
 
-    // Player 1 has the bottom row; player 2 the top row
-    fn current_player_row(&self) -> usize {
-        (self.current_player_index() + 1) % 2
+    // Both links are used up, the next ones have to hash to them
+    fn advance_chains(&mut self, p1_link: [u8; 32], p2_link: [u8; 32]) {
+        self.commitments = [p1_link, p2_link];
+        self.reveals = [None, None];
     }
 
-    // Plays card from player hand to specific board position on player's row
-    pub fn play_card(&mut self, pos: u8, card_index: u8) -> Result<()> {
-        require!(self.is_active(), CardGameError::GameAlreadyOver);
-
-        if pos <= MAX_ROW {
-
-            let player = self.current_player_index();
-            let row = self.current_player_row();
-            let pos = pos as usize;
-            let card_index = card_index as usize;
-
-            match self.board[row][pos] {
-
-                Some(_) => return Err(CardGameError::TileAlreadySet.into()),
-
-                None => {
-                    let card;
-                    if player == 0 {
-                        if self.p1_hand.len() <= card_index {
-                            return Err(CardGameError::CardIndexOutOfBounds.into())
-                        }
+    pub fn is_active(&self) -> bool {
+        self.state == GameState::Active
+    }
 
-                        card = self.p1_hand[card_index];
-                        if card.mana > self.mana[player] {
-                            return Err(CardGameError::InsufficientMana.into())
+    pub fn is_pending(&self) -> bool {
+        self.state == GameState::Pending
+    }
 
-                        }
-                        self.p1_hand.remove(card_index);
-                        self.mana[0] -= card.mana;
+    pub fn is_revealing(&self) -> bool {
+        self.state == GameState::Revealing
+    }
 
-                    } else {
-                        if self.p2_hand.len() <= card_index {
-                            return Err(CardGameError::CardIndexOutOfBounds.into())
-                        }
+    pub fn is_over(&self) -> bool {
+        matches!(self.state, GameState::Tie | GameState::Won { .. })
+    }
 
-                        card = self.p2_hand[card_index];
-                        if card.mana > self.mana[player] {
-                            return Err(CardGameError::InsufficientMana.into())
+    // Nobody is on turn before both seeds are revealed and the opening hands are dealt
+    // Once the player on turn ends it the next player is up, though they have to reveal their link before playing
+    pub fn current_player(&self) -> Pubkey {
+        if self.turn == 0 {
+            return Pubkey::default()
+        }
+        let player = engine::Game::player_on_turn(self.turn);
+        if self.reveals[player].is_some() {
+            return self.players[(player + 1) % 2]
+        }
+        self.players[player]
+    }
 
-                        }
-                        self.p2_hand.remove(card_index);
-                        self.mana[1] -= card.mana;
+    // Player holding the game up, either by not revealing their link or by not ending their turn
+    pub fn is_waiting_on(&self, player: Pubkey) -> bool {
+        match self.state {
+            GameState::Revealing => match self.players.iter().position(|key| *key == player) {
+                Some(index) => self.reveals[index].is_none(),
+                None => false,
+            },
+            GameState::Active => self.current_player() == player,
+            _ => false,
+        }
+    }
 
-                    }
-                    self.board[row][pos] = Some(card);
-                    
-                }
-            }
-        } else {
-            return Err(CardGameError::TileOutOfBounds.into())
+    // Only active games are handed to the engine, which has no notion of a pending challenge
+    fn to_engine(&self) -> engine::Game {
+        engine::Game {
+            turn: self.turn,
+            board: self.board.map(|row| row.map(|tile| tile.map(Into::into))),
+            state: engine::GameState::Active,
+            health: self.health,
+            mana: self.mana,
+            hands: [to_engine_cards(&self.p1_hand), to_engine_cards(&self.p2_hand)],
+            decks: [to_engine_cards(&self.p1_deck), to_engine_cards(&self.p2_deck)],
+            seed: self.seed,
         }
+    }
 
-        self.update_state();
+    fn load_engine(&mut self, game: engine::Game) {
+        let [p1_hand, p2_hand] = game.hands;
+        let [p1_deck, p2_deck] = game.decks;
+
+        self.turn = game.turn;
+        self.board = game.board.map(|row| row.map(|tile| tile.map(Into::into)));
+        self.state = match game.state {
+            engine::GameState::Active => GameState::Active,
+            engine::GameState::Tie => GameState::Tie,
+            engine::GameState::Won { winner } => GameState::Won { winner: self.players[winner] },
+        };
+        self.health = game.health;
+        self.mana = game.mana;
+        self.p1_hand = p1_hand.into_iter().map(Into::into).collect();
+        self.p2_hand = p2_hand.into_iter().map(Into::into).collect();
+        self.p1_deck = p1_deck.into_iter().map(Into::into).collect();
+        self.p2_deck = p2_deck.into_iter().map(Into::into).collect();
+        self.seed = game.seed;
+    }
 
+    // Applies a move through the engine, the account is left untouched if the move is rejected
+    // Nothing is played while a turn is waiting to be dealt
+    fn run(&mut self, action: impl FnOnce(&mut engine::Game) -> engine::Result<()>) -> Result<()> {
+        require!(self.is_active(), CardGameError::GameAlreadyOver);
+        require!(self.reveals == [None, None], CardGameError::TurnSeedNotRevealed);
 
+        let mut game = self.to_engine();
+        action(&mut game).map_err(CardGameError::from)?;
+        self.load_engine(game);
         Ok(())
     }
 
+    // Plays card from player hand to specific board position on player's row
+    // then resolves its battlecry, if any, on the optional target
+    pub fn play_card(&mut self, pos: u8, card_index: u8, target: Option<EffectTarget>) -> Result<()> {
+        self.run(|game| game.play_card(pos, card_index, target.map(Into::into)))
+    }
 
-    // Check if either hero is 0hp or less or if game can continue
-    fn update_state(& mut self) {
-        
-        if self.health[0] <= 0 && self.health[1] > 0 {
-            self.state = GameState::Won {
-                winner: self.players[1],
-            }
-        } else if self.health[0] > 0 && self.health[1] <= 0 {
-            self.state = GameState::Won {
-                winner: self.players[0],
-            }
-        }
-
+    // Casts spell from player hand onto a unit, or onto a hero with pos = HERO_POS
+    // Row 0 is the top (player 2) row and row 1 the bottom (player 1) row
+    pub fn cast_spell(&mut self, card_index: u8, row: u8, pos: u8) -> Result<()> {
+        self.run(|game| game.cast_spell(card_index, row, pos))
+    }
 
-        // Tie game if no player has cards left and heros are still alive
-        for arr in self.board {
-            for cell in arr {
-                if cell.is_some() {
-                    return;
-                }
-            }
-        }
-        if self.p1_hand.is_empty() && self.p2_hand.is_empty() {
-            self.state = GameState::Tie;
-        }
+    // Finishes current player's turn with the next link of their chain, the next turn is dealt
+    // by reveal_seed once the opponent reveals theirs, and the opponent's turn limit starts now
+    pub fn end_turn(&mut self, link: [u8; 32], now: i64) -> Result<()> {
+        require!(self.is_active(), CardGameError::GameAlreadyOver);
+        require!(self.reveals == [None, None], CardGameError::TurnSeedNotRevealed);
+        self.reveal_link(engine::Game::player_on_turn(self.turn), link)?;
+        self.turn_started_at = now;
+        Ok(())
+    }
 
+    pub fn attack(&mut self, ally_pos: u8, target: AttackTarget) -> Result<()> {
+        self.run(|game| game.attack(ally_pos, target.into()))
     }
 
+    pub fn get_game_state(&self) -> GameState {
+        return self.state;
+    }
 
-    // Finishes current player's turn and iterates turn 
-    pub fn end_turn(& mut self) -> Result<()> {
-        require!(self.is_active(), CardGameError::GameAlreadyOver);
+    pub fn get_players(&self) -> [Pubkey; 2] {
+        self.players
+    }
 
-        // Reset unit moves, so they can move next turn
-        for mut unit in &mut self.board[self.current_player_row()] {
-            if let Some(card) = &mut unit {
-                card.moves = 1;
-            }
-        }
-        if self.mana[self.current_player_index()] < 10 {
-            self.mana[self.current_player_index()] = (self.turn as i8- 1) / 2 + 2;
-        }
-        self.turn += 1;
-        Ok(())
+    pub fn is_settled(&self) -> bool {
+        self.settled
     }
 
-    
-    // Checks user chosen units are valid then calls helper atk helper func
-    pub fn attack(&mut self, bot_pos: u8, top_pos: u8) -> Result<()> {
-        require!(self.is_active(), CardGameError::GameAlreadyOver);
-        
-        let _hero_pos = MAX_ROW + 1;
+    pub fn get_turn(&self) -> u8 {
+        self.turn
+    }
 
-        match (bot_pos, top_pos) {
-            (0..=MAX_ROW, 0..=MAX_ROW) =>
-                self.attack_unit(bot_pos as usize, top_pos as usize),
+    pub fn get_board(&self) -> &[[Option<Card>; 7]; 2] {
+        &self.board
+    }
 
-            (_hero_pos, 0..=MAX_ROW) | (0..=MAX_ROW, _hero_pos) => 
-                self.attack_hero(bot_pos as usize, top_pos as usize),
+    pub fn get_health(&self) -> [i8; 2] {
+        self.health
+    }
 
-            (_, _) =>
-                Err(CardGameError::PositionOutOfBounds.into())
-        }
+    pub fn get_mana(&self) -> [i8; 2] {
+        self.mana
     }
-    
-
-    // Deducts hp from the attacking and attacked unit
-    fn attack_unit(&mut self, bot_pos: usize, top_pos: usize) -> Result<()> {
-
-        let user_row = self.current_player_row();
-        // Use split_at_mut to borrow two mutable references
-        // One into bottom row, other into top row
-        let (top_row, bottom_row)
-            = self.board.split_at_mut(1);
-
-
-        if let (Some(bot_unit), Some(top_unit))
-            = (&mut bottom_row[0][bot_pos], &mut top_row[0][top_pos]) {
-                if user_row == 1 {
-                    if bot_unit.moves == 0 {
-                        return Err(CardGameError::UnitIsNotReady.into())
-                    } else {
-                        bot_unit.moves = 0;
-                    }
-                } else {
-                    if top_unit.moves == 0 {
-                        return Err(CardGameError::UnitIsNotReady.into())
-                    } else {
-                        top_unit.moves = 0;
-                    }
-                }
 
-                bot_unit.hp -= top_unit.atk;
-                top_unit.hp -= bot_unit.atk;
+    // player is 0 for player 1 and 1 for player 2
+    pub fn get_hand(&self, player: usize) -> &[Card] {
+        if player == 0 { &self.p1_hand } else { &self.p2_hand }
+    }
 
-                self.update_board();
-        } else {
-            return Err(CardGameError::EmptyBoardSpace.into())
-        }
+    pub fn get_deck(&self, player: usize) -> &[Card] {
+        if player == 0 { &self.p1_deck } else { &self.p2_deck }
+    }
 
-        self.update_state();
-        Ok(())
+    pub fn get_turn_limit(&self) -> i64 {
+        self.turn_limit
     }
 
-    // Deducts hp from the attacked hero
-    fn attack_hero(&mut self, bot_pos: usize, top_pos: usize) -> Result<()> {
-        // No update board run in this func since no unit can die
-        
-        // Attacking bottom hero
-        if bot_pos == 7 {
-
-            // Attacking self throw error
-            if self.current_player_index() == 0 {
-                return Err(CardGameError::CannotAttackOwnHero.into())
-
-            } else {
-                if let Some(unit) = &mut self.board[0][top_pos] {
-                    if unit.moves == 0 {
-                        return Err(CardGameError::UnitIsNotReady.into())
-                    } else {
-                        unit.moves = 0;
-                    }
-                    self.health[0] -= unit.atk;
-                } else {
-                    return Err(CardGameError::EmptyBoardSpace.into())
-                }
+    pub fn get_turn_started_at(&self) -> i64 {
+        self.turn_started_at
+    }
 
-            }
-        
-        // Attacking top hero
-        } else {
-
-            // Attacking self throw error
-            if self.current_player_index() == 1 {
-                return Err(CardGameError::CannotAttackOwnHero.into())
-
-            } else {
-                if let Some(unit) = & mut self.board[1][bot_pos] {
-                    if unit.moves == 0 {
-                        return Err(CardGameError::UnitIsNotReady.into())
-                    } else {
-                        unit.moves = 0;
-                    }
-                    self.health[1] -= unit.atk;
-                } else {
-                    return Err(CardGameError::EmptyBoardSpace.into())
-                }
-            }
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
 
-        }
+    pub fn get_commitments(&self) -> [[u8; 32]; 2] {
+        self.commitments
+    }
 
-        self.update_state();
-        Ok(())
+    pub fn get_reveals(&self) -> [Option<[u8; 32]>; 2] {
+        self.reveals
     }
 
-    // Clear out units with 0 or less hp
-    fn update_board(&mut self) {
-        for i in 0..2 {
+    // Hands the win to the other player, returning the winner
+    // Also the way out of a game neither player will reveal their seed for
+    pub fn concede(&mut self, loser: Pubkey) -> Result<Pubkey> {
+        require!(self.is_active() || self.is_revealing(), CardGameError::GameAlreadyOver);
+
+        let winner = match self.players.iter().position(|player| *player == loser) {
+            Some(index) => self.players[(index + 1) % 2],
+            None => return Err(CardGameError::MismatchPlayerKeys.into()),
+        };
+        self.state = GameState::Won { winner };
+        Ok(winner)
+    }
 
-            for j in 0..=MAX_ROW as usize {
+    // The waiting player wins once the current player has used up their turn limit
+    // A player who revealed their seed can claim the same way against one who won't
+    pub fn claim_timeout(&mut self, claimer: Pubkey, now: i64) -> Result<()> {
+        require!(self.is_active() || self.is_revealing(), CardGameError::GameAlreadyOver);
+        require!(self.players.contains(&claimer), CardGameError::MismatchPlayerKeys);
+        require!(!self.is_waiting_on(claimer), CardGameError::CannotClaimOwnTurn);
+        require!(
+            now.saturating_sub(self.turn_started_at) >= self.turn_limit,
+            CardGameError::TurnNotTimedOut
+        );
+        self.state = GameState::Won { winner: claimer };
+        Ok(())
+    }
 
-                if let Some(unit) = self.board[i][j] {
-                    if unit.hp <= 0 {
-                        self.board[i][j] = None;
-                    }
-                } 
-            }
-        }
+    // Scores for a finished game can only be handed out once
+    pub fn settle(&mut self) -> Result<()> {
+        require!(!self.settled, CardGameError::GameAlreadySettled);
+        require!(self.is_over(), CardGameError::GameStillActive);
+        self.settled = true;
+        Ok(())
     }
 
-    pub fn get_game_state(&self) -> GameState {
-        return self.state;
+    // Only games whose scores were handed out can be closed, pending ones are cancelled instead
+    pub fn check_closable(&self) -> Result<()> {
+        require!(self.is_over(), CardGameError::GameStillActive);
+        require!(self.settled, CardGameError::GameNotSettled);
+        Ok(())
     }
 
 
@@ -306,13 +374,228 @@ pub enum GameState {
     Active,
     Tie,
     Won { winner: Pubkey },
+    Pending,
+    // Both players committed to a seed, the game starts once both revealed it
+    Revealing,
+}
+
+// Spells resolve their effect straight away and never occupy a board tile
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy, PartialEq, Eq)]
+pub enum CardKind {
+    Unit,
+    Spell { effect: Effect },
+}
+
+impl CardKind {
+    pub const SIZE: usize = 1 + Effect::SIZE;
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Damage { amount: i8 },
+    Heal { amount: i8 },
+    Buff { atk: i8, hp: i8 },
+    HealOwnHero { amount: i8 },
+    BuffAdjacent { atk: i8, hp: i8 },
+    Summon { hp: i8, atk: i8 },
+    DamageEnemyHero { amount: i8 },
+    DamageRandomEnemy { amount: i8 },
+    DrawCard,
+}
+
+impl Effect {
+    pub const SIZE: usize = 1 + 2;
+}
+
+// Ongoing bonus given to allied units on the same row while the aura unit is alive
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy, PartialEq, Eq)]
+pub enum Aura {
+    Adjacent { atk: i8, hp: i8 },
+    OtherAllies { atk: i8, hp: i8 },
+}
+
+impl Aura {
+    pub const SIZE: usize = 1 + 2;
+}
+
+// Board coordinates of an effect target, pos = HERO_POS targets the hero behind the row
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy, PartialEq, Eq)]
+pub struct EffectTarget {
+    pub row: u8,
+    pub pos: u8,
+}
+
+// What an attacking unit goes for, unit positions are on the opponent's row
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy, PartialEq, Eq)]
+pub enum AttackTarget {
+    Unit { pos: u8 },
+    Hero,
 }
 
 // Card struct
+// Charge units can attack the turn they are played, rush units can only attack other units on that turn
+// hp and atk are the unit's own stats, aura_hp and aura_atk are recomputed from the board by Game
+// max_hp is what heals top hp up to, set when the unit enters the board and raised by buffs
 #[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, Copy)]
 pub struct Card {
     pub hp: i8, // 1
+    pub max_hp: i8, // 1
     pub atk: i8, // 1
     pub mana: i8, // 1
     pub moves: i8, // 1
+    pub taunt: bool, // 1
+    pub charge: bool, // 1
+    pub rush: bool, // 1
+    pub summoning_sick: bool, // 1
+    pub divine_shield: bool, // 1
+    pub kind: CardKind, // CardKind::SIZE
+    pub battlecry: Option<Effect>, // 1 + Effect::SIZE
+    pub deathrattle: Option<Effect>, // 1 + Effect::SIZE
+    pub aura: Option<Aura>, // 1 + Aura::SIZE
+    pub aura_atk: i8, // 1
+    pub aura_hp: i8, // 1
+}
+
+impl Card {
+    pub const SIZE: usize = 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + CardKind::SIZE + (1 + Effect::SIZE) * 2
+        + (1 + Aura::SIZE) + 1 + 1;
+
+    pub fn is_spell(&self) -> bool {
+        matches!(self.kind, CardKind::Spell { .. })
+    }
+}
+
+fn to_engine_cards(cards: &[Card]) -> Vec<engine::Card> {
+    cards.iter().map(|card| (*card).into()).collect()
+}
+
+// The account types mirror the engine's field for field so they can be serialized and show up in the IDL
+
+impl From<Card> for engine::Card {
+    fn from(card: Card) -> Self {
+        engine::Card {
+            hp: card.hp,
+            max_hp: card.max_hp,
+            atk: card.atk,
+            mana: card.mana,
+            moves: card.moves,
+            taunt: card.taunt,
+            charge: card.charge,
+            rush: card.rush,
+            summoning_sick: card.summoning_sick,
+            divine_shield: card.divine_shield,
+            kind: card.kind.into(),
+            battlecry: card.battlecry.map(Into::into),
+            deathrattle: card.deathrattle.map(Into::into),
+            aura: card.aura.map(Into::into),
+            aura_atk: card.aura_atk,
+            aura_hp: card.aura_hp,
+        }
+    }
+}
+
+impl From<engine::Card> for Card {
+    fn from(card: engine::Card) -> Self {
+        Card {
+            hp: card.hp,
+            max_hp: card.max_hp,
+            atk: card.atk,
+            mana: card.mana,
+            moves: card.moves,
+            taunt: card.taunt,
+            charge: card.charge,
+            rush: card.rush,
+            summoning_sick: card.summoning_sick,
+            divine_shield: card.divine_shield,
+            kind: card.kind.into(),
+            battlecry: card.battlecry.map(Into::into),
+            deathrattle: card.deathrattle.map(Into::into),
+            aura: card.aura.map(Into::into),
+            aura_atk: card.aura_atk,
+            aura_hp: card.aura_hp,
+        }
+    }
+}
+
+impl From<CardKind> for engine::CardKind {
+    fn from(kind: CardKind) -> Self {
+        match kind {
+            CardKind::Unit => engine::CardKind::Unit,
+            CardKind::Spell { effect } => engine::CardKind::Spell { effect: effect.into() },
+        }
+    }
+}
+
+impl From<engine::CardKind> for CardKind {
+    fn from(kind: engine::CardKind) -> Self {
+        match kind {
+            engine::CardKind::Unit => CardKind::Unit,
+            engine::CardKind::Spell { effect } => CardKind::Spell { effect: effect.into() },
+        }
+    }
+}
+
+impl From<Effect> for engine::Effect {
+    fn from(effect: Effect) -> Self {
+        match effect {
+            Effect::Damage { amount } => engine::Effect::Damage { amount },
+            Effect::Heal { amount } => engine::Effect::Heal { amount },
+            Effect::Buff { atk, hp } => engine::Effect::Buff { atk, hp },
+            Effect::HealOwnHero { amount } => engine::Effect::HealOwnHero { amount },
+            Effect::BuffAdjacent { atk, hp } => engine::Effect::BuffAdjacent { atk, hp },
+            Effect::Summon { hp, atk } => engine::Effect::Summon { hp, atk },
+            Effect::DamageEnemyHero { amount } => engine::Effect::DamageEnemyHero { amount },
+            Effect::DamageRandomEnemy { amount } => engine::Effect::DamageRandomEnemy { amount },
+            Effect::DrawCard => engine::Effect::DrawCard,
+        }
+    }
+}
+
+impl From<engine::Effect> for Effect {
+    fn from(effect: engine::Effect) -> Self {
+        match effect {
+            engine::Effect::Damage { amount } => Effect::Damage { amount },
+            engine::Effect::Heal { amount } => Effect::Heal { amount },
+            engine::Effect::Buff { atk, hp } => Effect::Buff { atk, hp },
+            engine::Effect::HealOwnHero { amount } => Effect::HealOwnHero { amount },
+            engine::Effect::BuffAdjacent { atk, hp } => Effect::BuffAdjacent { atk, hp },
+            engine::Effect::Summon { hp, atk } => Effect::Summon { hp, atk },
+            engine::Effect::DamageEnemyHero { amount } => Effect::DamageEnemyHero { amount },
+            engine::Effect::DamageRandomEnemy { amount } => Effect::DamageRandomEnemy { amount },
+            engine::Effect::DrawCard => Effect::DrawCard,
+        }
+    }
+}
+
+impl From<Aura> for engine::Aura {
+    fn from(aura: Aura) -> Self {
+        match aura {
+            Aura::Adjacent { atk, hp } => engine::Aura::Adjacent { atk, hp },
+            Aura::OtherAllies { atk, hp } => engine::Aura::OtherAllies { atk, hp },
+        }
+    }
+}
+
+impl From<engine::Aura> for Aura {
+    fn from(aura: engine::Aura) -> Self {
+        match aura {
+            engine::Aura::Adjacent { atk, hp } => Aura::Adjacent { atk, hp },
+            engine::Aura::OtherAllies { atk, hp } => Aura::OtherAllies { atk, hp },
+        }
+    }
+}
+
+impl From<EffectTarget> for engine::EffectTarget {
+    fn from(target: EffectTarget) -> Self {
+        engine::EffectTarget { row: target.row, pos: target.pos }
+    }
+}
+
+impl From<AttackTarget> for engine::AttackTarget {
+    fn from(target: AttackTarget) -> Self {
+        match target {
+            AttackTarget::Unit { pos } => engine::AttackTarget::Unit { pos },
+            AttackTarget::Hero => engine::AttackTarget::Hero,
+        }
+    }
 }
\ No newline at end of file
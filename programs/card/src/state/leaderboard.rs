@@ -1,33 +1,62 @@
 use anchor_lang::prelude::*;
-use crate::errors::CardGameError;
 
 #[account] 
 pub struct Leaderboard {
-    pub players: Vec<Pubkey>,
-    pub bump: u8,
+    pub bump: u8, // 1
+    pub entries: Vec<LeaderboardEntry>, // 4 + MAX_ENTRIES * LeaderboardEntry::SIZE, never more however many players register
+}
+
+// Layout before the top 50, every registered player in a fixed 9000 byte account. migrate_leaderboard brings it over
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyLeaderboard {
+    pub players: Vec<Pubkey>, // 4 + 32 per player
+    pub bump: u8, // 1
+}
+
+impl LegacyLeaderboard {
+    pub const ACC_SIZE: usize = 9000;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey, // 32
+    pub name: String, // 4 + 20
+    pub score: u16, // 2
+}
+
+impl LeaderboardEntry {
+    pub const SIZE: usize = 32 + (4 + 20) + 2;
 }
 
 impl Leaderboard {
+    // Only the best ranked players are kept on chain
+    pub const MAX_ENTRIES: usize = 50;
+
+    // Keeps a page under the 1024 byte return data limit
+    pub const MAX_PAGE_SIZE: usize = 15;
+
+    pub const MAX_ACC_SIZE: usize = 1 + (4 + Self::MAX_ENTRIES * LeaderboardEntry::SIZE);
+
     pub fn initialize(&mut self, acc_bump: u8) -> Result<()> {
-        self.players = Vec::new();
         self.bump = acc_bump;
+        self.entries = Vec::new();
         Ok(())
     }
-    
 
-    pub fn add_new_player(&mut self, new_player: Pubkey) -> Result<()> {
-        if self.players.contains(&new_player) {
-            return Err(CardGameError::UserAlreadyExists.into())
-        }
+    // Moves the player to their new rank, players that reached a score first stay ahead on ties
+    pub fn update_entry(&mut self, player: Pubkey, name: &str, score: u16) {
+        self.entries.retain(|entry| entry.player != player);
 
-        self.players.push(new_player);
-        Ok(())
+        let rank = self.entries.partition_point(|entry| entry.score >= score);
+        if rank < Self::MAX_ENTRIES {
+            self.entries.insert(rank, LeaderboardEntry { player, name: name.to_string(), score });
+            self.entries.truncate(Self::MAX_ENTRIES);
+        }
     }
 
-    pub fn check_if_created(&self, player: Pubkey) -> bool {
-        if self.players.contains(&player) {
-            return true
-        }
-        return false
+    // Entries from rank offset onwards, ranks start at 0
+    pub fn get_page(&self, offset: u32, limit: u8) -> Vec<LeaderboardEntry> {
+        let limit = (limit as usize).min(Self::MAX_PAGE_SIZE);
+        self.entries.iter().skip(offset as usize).take(limit).cloned().collect()
     }
 }
\ No newline at end of file
@@ -1,7 +1,13 @@
+pub use card_registry::*;
+pub use deck::*;
 pub use game::*;
 pub use leaderboard::*;
+pub use season::*;
 pub use user::*;
 
+pub mod card_registry;
+pub mod deck;
 pub mod game;
 pub mod leaderboard;
+pub mod season;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::errors::CardGameError;
+use crate::state::leaderboard::*;
+
+// The running season, ratings of players from earlier seasons are soft reset the next time they play
+#[account]
+pub struct Season {
+    pub admin: Pubkey, // 32
+    pub bump: u8, // 1
+    pub number: u32, // 4
+    pub start_ts: i64, // 8
+    pub end_ts: i64, // 8
+    pub duration: i64, // 8
+}
+
+// Final standings of a finished season, never written to again
+#[account]
+pub struct SeasonArchive {
+    pub number: u32, // 4
+    pub start_ts: i64, // 8
+    pub end_ts: i64, // 8
+    pub standings: Vec<LeaderboardEntry>, // 4 + MAX_ENTRIES * LeaderboardEntry::SIZE
+    pub bump: u8, // 1
+}
+
+impl Season {
+    pub const MAX_ACC_SIZE: usize = 32 + 1 + 4 + 8 + 8 + 8;
+
+    pub fn initialize(&mut self, admin: Pubkey, bump: u8, duration: i64, now: i64) -> Result<()> {
+        require!(duration > 0, CardGameError::InvalidSeasonDuration);
+        self.admin = admin;
+        self.bump = bump;
+        self.number = 1;
+        self.duration = duration;
+        self.start(now);
+        Ok(())
+    }
+
+    fn start(&mut self, now: i64) {
+        self.start_ts = now;
+        self.end_ts = now.saturating_add(self.duration);
+    }
+
+    // Archives the finished season's standings and opens the next one with an empty leaderboard
+    pub fn rollover(&mut self, archive: &mut SeasonArchive, archive_bump: u8, leaderboard: &mut Leaderboard, now: i64) -> Result<()> {
+        require!(now >= self.end_ts, CardGameError::SeasonNotOver);
+
+        archive.number = self.number;
+        archive.start_ts = self.start_ts;
+        archive.end_ts = now;
+        archive.standings = std::mem::take(&mut leaderboard.entries);
+        archive.bump = archive_bump;
+
+        self.number += 1;
+        self.start(now);
+        Ok(())
+    }
+}
+
+impl SeasonArchive {
+    pub const MAX_ACC_SIZE: usize = 4 + 8 + 8 + (4 + Leaderboard::MAX_ENTRIES * LeaderboardEntry::SIZE) + 1;
+}
\ No newline at end of file
@@ -6,10 +6,61 @@ pub struct UserStats {
     name: String, // 4 for length + 20 for name
     pub bump: u8, // 1
     active_game: Option<Pubkey>, // 1 + 32
+    games_played: u32, // 4
+    season: u32, // 4
 }
 
-impl UserStats {
+// Layout before ratings, with a flat score that moved 5 per game. migrate_user_stats brings these over
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyUserStats {
+    pub score: u16, // 2
+    pub name: String, // 4 for length + 20 for name
+    pub bump: u8, // 1
+    pub active_game: Option<Pubkey>, // 1 + 32
+}
+
+impl LegacyUserStats {
     pub const MAX_ACC_SIZE: usize = 2 + (4 + 20) + 1 + (1 + 32);
+}
+
+// Elo rating every new account starts from
+pub const STARTING_RATING: u16 = 1200;
+
+// How far a single game moves the rating, new accounts move faster until they are placed
+pub const K_FACTOR: i32 = 32;
+pub const PROVISIONAL_K_FACTOR: i32 = 64;
+pub const PROVISIONAL_GAMES: u32 = 10;
+
+// Soft resets past this many missed seasons would leave the rating at the start anyway
+const MAX_SEASON_RESETS: u32 = 16;
+
+// Expected score (out of 1000) for a player rated 0, 25, 50, .. 400 points above their opponent
+const EXPECTED_SCORE: [i32; 17] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+];
+
+// Rating differences past this point are treated as this point
+const MAX_RATING_DIFF: i32 = 400;
+const RATING_STEP: i32 = 25;
+
+// Interpolates the expected score of a player against an opponent, out of 1000
+fn expected_score(rating: u16, opponent_rating: u16) -> i32 {
+    let diff = (rating as i32 - opponent_rating as i32).clamp(-MAX_RATING_DIFF, MAX_RATING_DIFF);
+    let abs_diff = diff.abs();
+    let index = (abs_diff / RATING_STEP) as usize;
+    let expected = match EXPECTED_SCORE.get(index + 1) {
+        Some(next) => {
+            let low = EXPECTED_SCORE[index];
+            low + (next - low) * (abs_diff % RATING_STEP) / RATING_STEP
+        }
+        None => EXPECTED_SCORE[index],
+    };
+
+    if diff < 0 { 1000 - expected } else { expected }
+}
+
+impl UserStats {
+    pub const MAX_ACC_SIZE: usize = 2 + (4 + 20) + 1 + (1 + 32) + 4 + 4;
 
     pub fn check_active_game(&self) -> Option<Pubkey> {
         return self.active_game;
@@ -24,25 +75,85 @@ impl UserStats {
         self.active_game = None;
     }
 
+    // Leaves the active game alone if it belongs to a different game
+    pub fn clear_active_if(&mut self, game_pk: Pubkey) {
+        if self.active_game == Some(game_pk) {
+            self.active_game = None;
+        }
+    }
+
     pub fn initialize(&mut self, name: String, bump: u8) {
-        self.score = 0;
+        self.score = STARTING_RATING;
         self.name = name;
         self.bump = bump;
-        
+        self.games_played = 0;
+        // Caught up with the running season on the first settled game
+        self.season = 0;
     }
 
-
-    pub fn increase_score(&mut self) {
-        // Prevent overflow
-        if self.score <= 65530 {
-            self.score += 5;
+    // A flat score says nothing about a rating, so migrated players start over as provisional
+    // Games from before the upgrade can't be read any more, so the active game is dropped with them
+    pub fn from_legacy(legacy: LegacyUserStats) -> Self {
+        UserStats {
+            score: STARTING_RATING,
+            name: legacy.name,
+            bump: legacy.bump,
+            active_game: None,
+            games_played: 0,
+            season: 0,
         }
     }
 
-    pub fn decrease_score(&mut self) {
-        // Prevent overflow
-        if self.score >= 5 {
-            self.score -= 5;
+    pub fn get_score(&self) -> u16 {
+        self.score
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_games_played(&self) -> u32 {
+        self.games_played
+    }
+
+    pub fn get_season(&self) -> u32 {
+        self.season
+    }
+
+    // Pulls the rating halfway back to the start for every season missed since the player last played
+    pub fn sync_season(&mut self, season: u32) {
+        let missed = season.saturating_sub(self.season).min(MAX_SEASON_RESETS);
+        for _ in 0..missed {
+            self.score = ((self.score as u32 + STARTING_RATING as u32) / 2) as u16;
         }
+        self.season = self.season.max(season);
+    }
+
+    pub fn is_provisional(&self) -> bool {
+        self.games_played < PROVISIONAL_GAMES
+    }
+
+    // Moves the rating towards the result, actual is out of 1000 (win 1000, tie 500, loss 0)
+    fn update_rating(&mut self, opponent_rating: u16, actual: i32) {
+        let k_factor = if self.is_provisional() { PROVISIONAL_K_FACTOR } else { K_FACTOR };
+        let change = k_factor * (actual - expected_score(self.score, opponent_rating));
+        // Round to the nearest point rather than towards zero
+        let change = (change + change.signum() * 500) / 1000;
+
+        self.score = (self.score as i32 + change).clamp(0, u16::MAX as i32) as u16;
+        self.games_played = self.games_played.saturating_add(1);
+    }
+
+    // Both ratings are read before either is updated
+    pub fn record_win(winner: &mut UserStats, loser: &mut UserStats) {
+        let winner_rating = winner.score;
+        winner.update_rating(loser.score, 1000);
+        loser.update_rating(winner_rating, 0);
+    }
+
+    pub fn record_tie(p1: &mut UserStats, p2: &mut UserStats) {
+        let p1_rating = p1.score;
+        p1.update_rating(p2.score, 500);
+        p2.update_rating(p1_rating, 500);
     }
 }
\ No newline at end of file
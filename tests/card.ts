@@ -3,6 +3,91 @@ import { Program } from "@coral-xyz/anchor";
 import { Card } from "../target/types/card";
 import { expect } from "chai";
 import { publicKey } from "@coral-xyz/anchor/dist/cjs/utils";
+import { createHash, randomBytes } from "crypto";
+
+// Ids of the cards the tests keep in the card registry
+const FOOTMAN_ID = 0;
+const SHIELDBEARER_ID = 1;
+
+const BPF_LOADER_UPGRADEABLE_ID = new anchor.web3.PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+// Seconds a player has to end their turn before the opponent can claim the game
+const TURN_LIMIT = new anchor.BN(60);
+
+// Elo rating new user stats accounts start from
+const STARTING_RATING = 1200;
+
+// Players ranked on the leaderboard account
+const MAX_LEADERBOARD_ENTRIES = 50;
+
+// Leaderboard account size, it stays the same however many players register
+const LEADERBOARD_SIZE = 8 + 1 + 4 + MAX_LEADERBOARD_ENTRIES * (32 + 4 + 20 + 2);
+
+// Seasons started by the tests last 30 days
+const SEASON_DURATION = new anchor.BN(30 * 24 * 60 * 60);
+
+// Links in a hash chain, the same as the program's SEED_CHAIN_LENGTH
+const SEED_CHAIN_LENGTH = 256;
+
+// Seed chains each player committed to, keyed by game then player, link 0 is the commitment
+const chains = new Map<string, Buffer[]>();
+
+// Same hash as the program's seed_commitment, each link binds the player to the one below it
+function commitTo(game, player) {
+  const chain = [randomBytes(32)];
+  while (chain.length < SEED_CHAIN_LENGTH) {
+    chain.unshift(
+      createHash("sha256")
+        .update(Buffer.concat([player.publicKey.toBuffer(), chain[0]]))
+        .digest()
+    );
+  }
+  chains.set(game.publicKey.toBase58() + player.publicKey.toBase58(), chain);
+  return Array.from(chain[0]);
+}
+
+// Link 1 is revealed before the game starts and link n + 1 when turn n ends
+function linkOf(game, player, index) {
+  return Array.from(
+    chains.get(game.toBase58() + player.publicKey.toBase58())[index]
+  );
+}
+
+function card(hp, atk, mana, moves, keywords = {}) {
+  return {
+    hp,
+    maxHp: hp,
+    atk,
+    mana,
+    moves,
+    taunt: false,
+    charge: false,
+    rush: false,
+    summoningSick: false,
+    divineShield: false,
+    kind: { unit: {} },
+    battlecry: null,
+    deathrattle: null,
+    aura: null,
+    auraAtk: 0,
+    auraHp: 0,
+    ...keywords,
+  };
+}
+
+// Attack targets, unit positions are on the opponent's row
+const HERO = { hero: {} };
+
+function unitAt(pos) {
+  return { unit: { pos } };
+}
+
+// Board row with units at the given positions and empty tiles everywhere else
+function boardRow(units) {
+  return [0, 1, 2, 3, 4, 5, 6].map((pos) => units[pos] ?? null);
+}
 
 async function playCard(
   program: Program<Card>,
@@ -16,7 +101,7 @@ async function playCard(
   expectedMana
 ) {
   await program.methods
-    .playCard(pos, cardIndex)
+    .playCard(pos, cardIndex, null)
     .accounts({
       player: player.publicKey,
       game,
@@ -30,21 +115,33 @@ async function playCard(
   expect(gameState.mana).to.eql(expectedMana);
 }
 
-async function endTurn(
-  program: Program<Card>,
-  game,
-  player,
-  expectedTurn,
-  expectedBoard
-) {
+// Ends turn for the player on it and deals the next one with the opponent's reveal
+async function passTurn(program: Program<Card>, game, player, opponent) {
+  const { turn } = await program.account.game.fetch(game);
   await program.methods
-    .endTurn()
+    .endTurn(linkOf(game, player, turn + 1))
     .accounts({
       player: player.publicKey,
       game,
     })
     .signers(player instanceof (anchor.Wallet as any) ? [] : [player])
     .rpc();
+  await program.methods
+    .revealSeed(linkOf(game, opponent, turn + 1))
+    .accounts({ game, player: opponent.publicKey })
+    .signers(opponent instanceof (anchor.Wallet as any) ? [] : [opponent])
+    .rpc();
+}
+
+async function endTurn(
+  program: Program<Card>,
+  game,
+  player,
+  opponent,
+  expectedTurn,
+  expectedBoard
+) {
+  await passTurn(program, game, player, opponent);
   const gameState = await program.account.game.fetch(game);
   expect(gameState.turn).to.equal(expectedTurn);
   expect(gameState.board).to.eql(expectedBoard);
@@ -54,13 +151,13 @@ async function attack(
   program: Program<Card>,
   game,
   player,
-  botPos,
-  topPos,
+  allyPos,
+  target,
   expectedBoard,
   expectedHp
 ) {
   await program.methods
-    .attack(botPos, topPos)
+    .attack(allyPos, target)
     .accounts({
       player: player.publicKey,
       game,
@@ -126,6 +223,167 @@ async function getUserPDA(program, key) {
   return obj;
 }
 
+async function getDeckPDA(program, key, index) {
+  const obj = await publicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("deck"),
+      key.toBuffer(),
+      Buffer.from([index]),
+    ],
+    program.programId
+  );
+
+  return obj;
+}
+
+async function getLeaderboardPDA(program) {
+  const obj = await publicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("leaderboard")],
+    program.programId
+  );
+
+  return obj;
+}
+
+async function getSeasonPDA(program) {
+  const obj = await publicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("season")],
+    program.programId
+  );
+
+  return obj;
+}
+
+async function getSeasonArchivePDA(program, number) {
+  const obj = await publicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("season-archive"),
+      new anchor.BN(number).toArrayLike(Buffer, "le", 4),
+    ],
+    program.programId
+  );
+
+  return obj;
+}
+
+// Program data account of the deployed program, it holds the upgrade authority
+async function getProgramDataPDA(program) {
+  const obj = await publicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+
+  return obj;
+}
+
+async function getCardRegistryPDA(program) {
+  const obj = await publicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("card-registry")],
+    program.programId
+  );
+
+  return obj;
+}
+
+async function createOrUpdateDeck(program, player, index, cards) {
+  const [deckPDA, _] = await getDeckPDA(program, player.publicKey, index);
+  const [cardRegistryPDA, _bump] = await getCardRegistryPDA(program);
+  const signers = player instanceof (anchor.Wallet as any) ? [] : [player];
+  try {
+    await program.methods
+      .createDeck(index, cards)
+      .accounts({
+        owner: player.publicKey,
+        deck: deckPDA,
+        cardRegistry: cardRegistryPDA,
+      })
+      .signers(signers)
+      .rpc();
+  } catch (error) {
+    console.log("Deck already exists, updating it instead");
+    await program.methods
+      .updateDeck(cards)
+      .accounts({
+        owner: player.publicKey,
+        deck: deckPDA,
+        cardRegistry: cardRegistryPDA,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  const deck = await program.account.deck.fetch(deckPDA);
+  expect(deck.cards).to.eql(cards);
+  expect(deck.owner).to.eql(player.publicKey);
+  return deckPDA;
+}
+
+async function createChallenge(program, game, challenger, opponent) {
+  const [challengerPDA, _challengerBump] = await getUserPDA(
+    program,
+    challenger.publicKey
+  );
+  const [opponentPDA, _opponentBump] = await getUserPDA(
+    program,
+    opponent.publicKey
+  );
+  const [deckPDA, _deckBump] = await getDeckPDA(
+    program,
+    challenger.publicKey,
+    0
+  );
+  const [cardRegistryPDA, _registryBump] = await getCardRegistryPDA(program);
+
+  await program.methods
+    .createChallenge(TURN_LIMIT, commitTo(game, challenger))
+    .accounts({
+      game: game.publicKey,
+      p1: challenger.publicKey,
+      p1Stats: challengerPDA,
+      p2: opponent.publicKey,
+      p2Stats: opponentPDA,
+      p1Deck: deckPDA,
+      cardRegistry: cardRegistryPDA,
+    })
+    .signers(
+      challenger instanceof (anchor.Wallet as any) ? [game] : [challenger, game]
+    )
+    .rpc();
+}
+
+async function acceptChallenge(program, game, challenger, opponent) {
+  const [opponentPDA, _] = await getUserPDA(program, opponent.publicKey);
+  const [deckPDA, _deckBump] = await getDeckPDA(program, opponent.publicKey, 0);
+  const [cardRegistryPDA, _registryBump] = await getCardRegistryPDA(program);
+
+  await program.methods
+    .acceptChallenge(commitTo(game, opponent))
+    .accounts({
+      game: game.publicKey,
+      p1: challenger.publicKey,
+      p2: opponent.publicKey,
+      p2Stats: opponentPDA,
+      p2Deck: deckPDA,
+      cardRegistry: cardRegistryPDA,
+    })
+    .signers(opponent instanceof (anchor.Wallet as any) ? [] : [opponent])
+    .rpc();
+}
+
+async function revealSeed(program, game, player) {
+  await program.methods
+    .revealSeed(linkOf(game.publicKey, player, 1))
+    .accounts({ game: game.publicKey, player: player.publicKey })
+    .signers(player instanceof (anchor.Wallet as any) ? [] : [player])
+    .rpc();
+}
+
+// The game only starts once both players revealed the first link below their commitments
+async function revealSeeds(program, game, challenger, opponent) {
+  await revealSeed(program, game, challenger);
+  await revealSeed(program, game, opponent);
+}
+
 async function createUserStat(program, pubkey, pda, bump, name, leaderboard) {
   await program.methods
     .createUserStats(name)
@@ -138,13 +396,23 @@ async function createUserStat(program, pubkey, pda, bump, name, leaderboard) {
 
   const UserState = await program.account.userStats.fetch(pda);
   expect(UserState.name).eql(name);
-  expect(UserState.score).eql(0);
+  expect(UserState.score).eql(STARTING_RATING);
+  expect(UserState.gamesPlayed).eql(0);
   expect(UserState.bump).eql(bump);
 
-  const registeredPlayers = await (
-    await program.account.leaderboard.fetch(leaderboard)
-  ).players;
-  expect(registeredPlayers[registeredPlayers.length - 1]).to.eql(pubkey);
+  // New players are ranked straight away while the top 50 still has room
+  const entries = (await program.account.leaderboard.fetch(leaderboard))
+    .entries;
+  if (entries.length < MAX_LEADERBOARD_ENTRIES) {
+    expect(entries.map((entry) => entry.player.toBase58())).to.include(
+      pubkey.toBase58()
+    );
+  }
+
+  const leaderboardInfo = await program.provider.connection.getAccountInfo(
+    leaderboard
+  );
+  expect(leaderboardInfo.data.length).to.equal(LEADERBOARD_SIZE);
 }
 
 async function concede(program, kp, pda) {
@@ -157,6 +425,8 @@ async function concede(program, kp, pda) {
   }
   const gamePk = new anchor.web3.PublicKey(userState.activeGame);
   const gameState = await program.account.game.fetch(gamePk);
+  const [leaderboardPDA, _leaderboardBump] = await getLeaderboardPDA(program);
+  const [seasonPDA, _seasonBump] = await getSeasonPDA(program);
   const playerList = gameState.players;
   const opponentPkObj =
     playerList[0].toBase58() == kp.publicKey.toBase58()
@@ -164,14 +434,48 @@ async function concede(program, kp, pda) {
       : playerList[0];
   const opponentPk = new anchor.web3.PublicKey(opponentPkObj);
   const [opponentPDA, _] = await getUserPDA(program, opponentPkObj);
+  if (gameState.state.pending) {
+    await program.methods
+      .cancelChallenge()
+      .accounts({
+        game: gamePk,
+        p1: kp.publicKey,
+        p1Stats: pda,
+        p2: opponentPk,
+        p2Stats: opponentPDA,
+      })
+      .signers(kp instanceof (anchor.Wallet as any) ? [] : [kp])
+      .rpc();
+    return;
+  }
+  if (!gameState.state.active && !gameState.state.revealing) {
+    console.log("Concede function called on a finished game, settling it");
+    const [p1PDA, _p1Bump] = await getUserPDA(program, playerList[0]);
+    const [p2PDA, _p2Bump] = await getUserPDA(program, playerList[1]);
+    await program.methods
+      .setScores()
+      .accounts({
+        p1: playerList[0],
+        p2: playerList[1],
+        p1Stats: p1PDA,
+        p2Stats: p2PDA,
+        game: gamePk,
+        leaderboard: leaderboardPDA,
+        season: seasonPDA,
+      })
+      .rpc();
+    return;
+  }
   await program.methods
     .concede()
     .accounts({
       game: gamePk,
-      p1: kp.publicKey,
-      p1Stats: pda,
-      p2: opponentPk,
-      p2Stats: opponentPDA,
+      player: kp.publicKey,
+      playerStats: pda,
+      opponent: opponentPk,
+      opponentStats: opponentPDA,
+      leaderboard: leaderboardPDA,
+      season: seasonPDA,
     })
     .signers(kp instanceof (anchor.Wallet as any) ? [] : [kp])
     .rpc();
@@ -189,15 +493,11 @@ describe("card", () => {
   const p2 = anchor.web3.Keypair.generate();
   const p3 = anchor.web3.Keypair.generate();
 
-  let sampleHand = [
-    { hp: 10, atk: 10, mana: 1, moves: 0 },
-    { hp: 7, atk: 3, mana: 2, moves: 0 },
-  ];
-
-  let sampleHand2 = [
-    { hp: 15, atk: 7, mana: 1, moves: 0 },
-    { hp: 5, atk: 5, mana: 2, moves: 0 },
-  ];
+  // Single card decks keep the random draws predictable
+  const footman = card(3, 2, 1, 0);
+  const shieldbearer = card(4, 1, 1, 0, { taunt: true });
+  const p1Deck = Array(30).fill(FOOTMAN_ID);
+  const p2Deck = Array(30).fill(SHIELDBEARER_ID);
 
   it("Test init leaderboard function", async () => {
     const [leaderboardPDA, leaderboardBump] =
@@ -312,71 +612,287 @@ describe("card", () => {
     }
   });
 
-  it("setup game!", async () => {
-    const [leaderboardPDA, leaderboardBump] =
-      await publicKey.findProgramAddressSync(
-        [anchor.utils.bytes.utf8.encode("leaderboard")],
-        program.programId
+  it("Test season functions", async () => {
+    const [seasonPDA, _seasonBump] = await getSeasonPDA(program);
+    const [programDataPDA, _programDataBump] = await getProgramDataPDA(program);
+    const [leaderboardPDA, _leaderboardBump] = await getLeaderboardPDA(program);
+
+    try {
+      await program.methods
+        .initSeason(SEASON_DURATION)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          programData: programDataPDA,
+          season: seasonPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log(
+        "Init season test, expect already in use error if not first time testing:",
+        error.logs[3]
       );
+    }
+
+    const season = await program.account.season.fetch(seasonPDA);
+    expect(season.number).to.be.at.least(1);
+    expect(season.endTs.gt(season.startTs)).to.be.true;
+
+    const [archivePDA, _archiveBump] = await getSeasonArchivePDA(
+      program,
+      season.number
+    );
+    try {
+      await program.methods
+        .rolloverSeason()
+        .accounts({
+          admin: provider.wallet.publicKey,
+          season: seasonPDA,
+          seasonArchive: archivePDA,
+          leaderboard: leaderboardPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("Expect season not over:", error.error["errorCode"]);
+    }
+    expect(await program.account.seasonArchive.fetchNullable(archivePDA)).to.be
+      .null;
+  });
+
+  it("Test init card registry function", async () => {
+    const [cardRegistryPDA, _] = await getCardRegistryPDA(program);
+    const [programDataPDA, _programDataBump] = await getProgramDataPDA(program);
 
+    // Only the upgrade authority the tests deploy with can open the registry
+    try {
+      await program.methods
+        .initCardRegistry()
+        .accounts({
+          admin: p2.publicKey,
+          programData: programDataPDA,
+          cardRegistry: cardRegistryPDA,
+        })
+        .signers([p2])
+        .rpc();
+    } catch (error) {
+      console.log("Expect not upgrade authority:", error.error["errorCode"]);
+    }
+
+    try {
+      await program.methods
+        .initCardRegistry()
+        .accounts({
+          admin: provider.wallet.publicKey,
+          programData: programDataPDA,
+          cardRegistry: cardRegistryPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log(
+        "Init card registry test, expect already in use error if not first time testing:",
+        error.logs[3]
+      );
+    }
+
+    // Test cards have to sit at their ids whether or not the registry is new
+    const registry = await program.account.cardRegistry.fetch(cardRegistryPDA);
+    const testCards = [footman, shieldbearer];
+    for (let id = 0; id < testCards.length; id++) {
+      if (id < registry.cards.length) {
+        await program.methods
+          .updateCard(id, testCards[id])
+          .accounts({
+            admin: provider.wallet.publicKey,
+            cardRegistry: cardRegistryPDA,
+          })
+          .rpc();
+      } else {
+        await program.methods
+          .addCard(testCards[id])
+          .accounts({
+            admin: provider.wallet.publicKey,
+            cardRegistry: cardRegistryPDA,
+          })
+          .rpc();
+      }
+    }
+
+    const updated = await program.account.cardRegistry.fetch(cardRegistryPDA);
+    expect(updated.cards.slice(0, testCards.length)).to.eql(testCards);
+
+    try {
+      await program.methods
+        .addCard(card(127, 127, 1, 0))
+        .accounts({
+          admin: p2.publicKey,
+          cardRegistry: cardRegistryPDA,
+        })
+        .signers([p2])
+        .rpc();
+    } catch (error) {
+      console.log("Expect not registry admin:", error.error["errorCode"]);
+    }
+  });
+
+  it("Test deck create function", async () => {
+    await createOrUpdateDeck(program, p1, 0, p1Deck);
+    await createOrUpdateDeck(program, p2, 0, p2Deck);
+    await createOrUpdateDeck(program, p3, 0, p2Deck);
+
+    const [p1DeckPDA, _] = await getDeckPDA(program, p1.publicKey, 0);
+    const [cardRegistryPDA, _bump] = await getCardRegistryPDA(program);
+    try {
+      await program.methods
+        .updateDeck(Array(31).fill(FOOTMAN_ID))
+        .accounts({
+          owner: p1.publicKey,
+          deck: p1DeckPDA,
+          cardRegistry: cardRegistryPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("Expect deck too large:", error.error["errorCode"]);
+    }
+
+    try {
+      await program.methods
+        .updateDeck([FOOTMAN_ID, 999])
+        .accounts({
+          owner: p1.publicKey,
+          deck: p1DeckPDA,
+          cardRegistry: cardRegistryPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("Expect unknown card id:", error.error["errorCode"]);
+    }
+  });
+
+  it("Test challenge functions", async () => {
     const [p1PDA, p1Bump] = await getUserPDA(program, p1.publicKey);
     const [p2PDA, p2Bump] = await getUserPDA(program, p2.publicKey);
-    const [p3PDA, p3Bump] = await getUserPDA(program, p3.publicKey);
 
-    console.log("Setting up game between p1 and p2");
     await concede(program, p1, p1PDA);
-    console.log("End of concede call");
-    const testgame = anchor.web3.Keypair.generate();
+    await concede(program, p2, p2PDA);
+
+    console.log("Challenging a player without a user stats account");
+    const unregistered = anchor.web3.Keypair.generate();
+    const unregisteredGame = anchor.web3.Keypair.generate();
+    try {
+      await createChallenge(program, unregisteredGame, p1, unregistered);
+    } catch (error) {
+      console.log(
+        "Expect opponent stats account not initialized:",
+        error.error["errorCode"]
+      );
+    }
+    expect(await program.account.game.fetchNullable(unregisteredGame.publicKey))
+      .to.be.null;
+
+    console.log("Player 2 declines a challenge");
+    const declined = anchor.web3.Keypair.generate();
+    await createChallenge(program, declined, p1, p2);
+    let gameState = await program.account.game.fetch(declined.publicKey);
+    expect(gameState.state).to.eql({ pending: {} });
+    expect((await program.account.userStats.fetch(p1PDA)).activeGame).to.eql(
+      declined.publicKey
+    );
+    expect((await program.account.userStats.fetch(p2PDA)).activeGame).to.eql(
+      null
+    );
+
+    try {
+      await program.methods
+        .endTurn(linkOf(declined.publicKey, p1, 2))
+        .accounts({ player: p1.publicKey, game: declined.publicKey })
+        .rpc();
+    } catch (error) {
+      console.log("Expect game not started:", error.error["errorCode"]);
+    }
+
     await program.methods
-      .setupGame(sampleHand, sampleHand2)
+      .declineChallenge()
       .accounts({
-        game: testgame.publicKey,
+        game: declined.publicKey,
         p1: p1.publicKey,
         p1Stats: p1PDA,
         p2: p2.publicKey,
         p2Stats: p2PDA,
-        leaderboard: leaderboardPDA,
       })
-      .signers([testgame])
+      .signers([p2])
       .rpc();
-    await concede(program, p2, p2PDA);
+    expect(await program.account.game.fetchNullable(declined.publicKey)).to.be
+      .null;
+    expect((await program.account.userStats.fetch(p1PDA)).activeGame).to.eql(
+      null
+    );
 
-    const state = await program.account.userStats.fetch(p1PDA);
-    console.log(
-      `P1 current score ${state.score} with game state: ${state.activeGame}`
+    console.log("Player 1 cancels a challenge");
+    const cancelled = anchor.web3.Keypair.generate();
+    await createChallenge(program, cancelled, p1, p2);
+    await concede(program, p1, p1PDA);
+    expect(await program.account.game.fetchNullable(cancelled.publicKey)).to.be
+      .null;
+    expect((await program.account.userStats.fetch(p1PDA)).activeGame).to.eql(
+      null
     );
+  });
+
+  it("setup game!", async () => {
+    const [leaderboardPDA, _leaderboardBump] = await getLeaderboardPDA(program);
+    const [seasonPDA, _seasonBump] = await getSeasonPDA(program);
+    const [p1PDA, p1Bump] = await getUserPDA(program, p1.publicKey);
+    const [p2PDA, p2Bump] = await getUserPDA(program, p2.publicKey);
+    const [p3PDA, p3Bump] = await getUserPDA(program, p3.publicKey);
+
+    console.log("Setting up game between p1 and p2");
+    await concede(program, p1, p1PDA);
+    console.log("End of concede call");
+    const testgame = anchor.web3.Keypair.generate();
+    await createChallenge(program, testgame, p1, p2);
+    await acceptChallenge(program, testgame, p1, p2);
+    const revealingGame = await program.account.game.fetch(testgame.publicKey);
+    expect(revealingGame.state).to.eql({ revealing: {} });
+    expect(revealingGame.turn).to.equal(0);
+    await revealSeeds(program, testgame, p1, p2);
+    await concede(program, p2, p2PDA);
+
+    const concededGame = await program.account.game.fetch(testgame.publicKey);
+    expect(concededGame.state).to.eql({ won: { winner: p1.publicKey } });
+    expect(concededGame.settled).to.be.true;
 
     try {
       await program.methods
-        .setupGame(sampleHand, sampleHand2)
+        .concede()
         .accounts({
-          game: gameKP.publicKey,
-          p1: p1.publicKey,
-          p1Stats: p1PDA,
-          p2: p2.publicKey,
-          p2Stats: p2PDA,
+          game: testgame.publicKey,
+          player: p1.publicKey,
+          playerStats: p1PDA,
+          opponent: p2.publicKey,
+          opponentStats: p2PDA,
           leaderboard: leaderboardPDA,
+          season: seasonPDA,
         })
-        .signers([gameKP])
         .rpc();
+    } catch (error) {
+      console.log("Expect game already over:", error.error["errorCode"]);
+    }
+
+    const state = await program.account.userStats.fetch(p1PDA);
+    console.log(
+      `P1 current score ${state.score} with game state: ${state.activeGame}`
+    );
+
+    try {
+      await createChallenge(program, gameKP, p1, p2);
+      await acceptChallenge(program, gameKP, p1, p2);
+      await revealSeeds(program, gameKP, p1, p2);
     } catch (error) {
       console.log("Setup game 1 creation:", error);
     }
 
     console.log("Setting up game between p2 and p3");
     try {
-      await program.methods
-        .setupGame(sampleHand, sampleHand2)
-        .accounts({
-          game: gameKP2.publicKey,
-          p1: p2.publicKey,
-          p1Stats: p2PDA,
-          p2: p3.publicKey,
-          p2Stats: p3PDA,
-          leaderboard: leaderboardPDA,
-        })
-        .signers([p2, gameKP2])
-        .rpc();
+      await createChallenge(program, gameKP2, p2, p3);
     } catch (error) {
       console.log(
         "Setup game 2 creation expecting already active error:",
@@ -400,9 +916,37 @@ describe("card", () => {
 
     expect(gameState.mana).to.eql([1, 1]);
 
-    expect(gameState.p1Hand).to.eql(sampleHand);
+    expect(gameState.p1Hand).to.eql([footman, footman, footman]);
+
+    expect(gameState.p2Hand).to.eql([
+      shieldbearer,
+      shieldbearer,
+      shieldbearer,
+    ]);
+
+    expect(gameState.p1Deck.length).to.equal(27);
+
+    expect(gameState.p2Deck.length).to.equal(27);
+
+    expect(gameState.turnLimit.toNumber()).to.equal(TURN_LIMIT.toNumber());
 
-    expect(gameState.p2Hand).to.eql(sampleHand2);
+    try {
+      await program.methods
+        .claimTimeout()
+        .accounts({
+          game: gameKP.publicKey,
+          player: p2.publicKey,
+          playerStats: p2PDA,
+          opponent: p1.publicKey,
+          opponentStats: p1PDA,
+          leaderboard: leaderboardPDA,
+          season: seasonPDA,
+        })
+        .signers([p2])
+        .rpc();
+    } catch (error) {
+      console.log("Expect turn not timed out:", error.error["errorCode"]);
+    }
   });
 
   it("Test game functions", async () => {
@@ -412,12 +956,19 @@ describe("card", () => {
         program.programId
       );
 
+    const [seasonPDA, _seasonBump] = await getSeasonPDA(program);
+
     const [p1PDA, p1Bump] = await getUserPDA(program, p1.publicKey);
     const [p2PDA, p2Bump] = await getUserPDA(program, p2.publicKey);
     const [p3PDA, p3Bump] = await getUserPDA(program, p3.publicKey);
     // console.log("Initial state: ");
     // await printBoard(program, gameKP.publicKey);
 
+    const sick = { summoningSick: true };
+    const taunt = { taunt: true };
+    // Damaged units keep the hp they were played with as their max
+    const hurt = (maxHp) => ({ maxHp });
+
     console.log("Turn 1: Player 1 plays card");
     await playCard(
       program,
@@ -427,44 +978,29 @@ describe("card", () => {
       6,
       1,
       { active: {} },
-      [
-        [null, null, null, null, null, null, null],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 0 },
-        ],
-      ],
+      [boardRow({}), boardRow({ 6: card(3, 2, 1, 0, sick) })],
       [0, 1]
     );
-    // await printBoard(program, gameKP.publicKey);
 
     try {
-      await attack(program, gameKP.publicKey, p1, 6, 7, null, null);
+      await attack(program, gameKP.publicKey, p1, 6, HERO, null, null);
+    } catch (error) {
+      console.log("Expect unit not ready yet:", error.error["errorCode"]);
+    }
+
+    try {
+      await attack(program, gameKP.publicKey, p1, 6, unitAt(7), null, null);
     } catch (error) {
-      console.log("Expect unit not ready yet:", error.logs[2]);
+      console.log("Expect position out of bounds:", error.error["errorCode"]);
     }
 
     console.log("Player 1 ends turn");
-    await endTurn(program, gameKP.publicKey, p1, 2, [
-      [null, null, null, null, null, null, null],
-      [
-        null,
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
+    await endTurn(program, gameKP.publicKey, p1, p2, 2, [
+      boardRow({}),
+      boardRow({ 6: card(3, 2, 1, 1) }),
     ]);
-    // await printBoard(program, gameKP.publicKey);
 
-    console.log("Turn 2: Player 2 plays card");
+    console.log("Turn 2: Player 2 plays taunt card");
     await playCard(
       program,
       gameKP.publicKey,
@@ -474,250 +1010,36 @@ describe("card", () => {
       2,
       { active: {} },
       [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 0 },
-          null,
-          null,
-          null,
-          null,
-          null,
-        ],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 1 },
-        ],
+        boardRow({ 1: card(4, 1, 1, 0, { ...taunt, ...sick }) }),
+        boardRow({ 6: card(3, 2, 1, 1) }),
       ],
       [2, 0]
     );
-    await printBoard(program, gameKP.publicKey);
 
     console.log("Player 2 ends turn");
-    await endTurn(program, gameKP.publicKey, p2, 3, [
-      [
-        null,
-        { hp: 15, atk: 7, mana: 1, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-      ],
-      [
-        null,
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
-    ]);
-
-    console.log("Turn 3: Player 1 attacks player 2 hero");
-    await attack(
-      program,
-      gameKP.publicKey,
-      p1,
-      6,
-      7,
-      [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-        ],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 0 },
-        ],
-      ],
-      [30, 20]
-    );
-
-    console.log("Player 1 ends turn");
-    await endTurn(program, gameKP.publicKey, p1, 4, [
-      [
-        null,
-        { hp: 15, atk: 7, mana: 1, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-      ],
-      [
-        null,
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
+    await endTurn(program, gameKP.publicKey, p2, p1, 3, [
+      boardRow({ 1: card(4, 1, 1, 1, taunt) }),
+      boardRow({ 6: card(3, 2, 1, 1) }),
     ]);
 
     try {
-      await playCard(
-        program,
-        gameKP.publicKey,
-        p2,
-        0,
-        1,
-        4,
-        { active: {} },
-        [
-          [
-            null,
-            { hp: 15, atk: 7, mana: 1, moves: 1 },
-            null,
-            { hp: 5, atk: 5, mana: 2, moves: 0 },
-            null,
-            null,
-            null,
-          ],
-          [
-            null,
-            null,
-            null,
-            null,
-            null,
-            null,
-            { hp: 10, atk: 10, mana: 1, moves: 1 },
-          ],
-        ],
-        [2, 0]
-      );
+      await attack(program, gameKP.publicKey, p1, 6, HERO, null, null);
     } catch (error) {
-      console.log("expecting already set error: ", error.error["errorCode"]);
+      console.log("Expect taunt unit in the way:", error.error["errorCode"]);
     }
 
-    console.log("Player 2 plays card");
-    await playCard(
-      program,
-      gameKP.publicKey,
-      p2,
-      0,
-      3,
-      4,
-      { active: {} },
-      [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 1 },
-          null,
-          { hp: 5, atk: 5, mana: 2, moves: 0 },
-          null,
-          null,
-          null,
-        ],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 1 },
-        ],
-      ],
-      [3, 0]
-    );
-
-    console.log("Turn 4: Player 2 attacks player 1 hero");
-    await attack(
-      program,
-      gameKP.publicKey,
-      p2,
-      7,
-      1,
-      [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 0 },
-          null,
-          { hp: 5, atk: 5, mana: 2, moves: 0 },
-          null,
-          null,
-          null,
-        ],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 1 },
-        ],
-      ],
-      [23, 20]
-    );
-
-    console.log("Player 2 ends turn");
-    await endTurn(program, gameKP.publicKey, p2, 5, [
-      [
-        null,
-        { hp: 15, atk: 7, mana: 1, moves: 1 },
-        null,
-        { hp: 5, atk: 5, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-      ],
-      [
-        null,
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
-    ]);
-
-    console.log("Turn 5: Player 1 attacks player 2 hero");
+    console.log("Turn 3: Player 1 attacks player 2's taunt unit");
     await attack(
       program,
       gameKP.publicKey,
       p1,
       6,
-      7,
+      unitAt(1),
       [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 1 },
-          null,
-          { hp: 5, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          null,
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 0 },
-        ],
+        boardRow({ 1: card(2, 1, 1, 1, { ...taunt, ...hurt(4) }) }),
+        boardRow({ 6: card(2, 2, 1, 0, hurt(3)) }),
       ],
-      [23, 10]
+      [30, 30]
     );
 
     console.log("Player 1 plays card");
@@ -727,269 +1049,123 @@ describe("card", () => {
       p1,
       0,
       0,
-      5,
+      3,
       { active: {} },
       [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 1 },
-          null,
-          { hp: 5, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 7, atk: 3, mana: 2, moves: 0 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 0 },
-        ],
+        boardRow({ 1: card(2, 1, 1, 1, { ...taunt, ...hurt(4) }) }),
+        boardRow({ 0: card(3, 2, 1, 0, sick), 6: card(2, 2, 1, 0, hurt(3)) }),
       ],
-      [1, 3]
+      [1, 2]
     );
 
     console.log("Player 1 ends turn");
-    await endTurn(program, gameKP.publicKey, p1, 6, [
-      [
-        null,
-        { hp: 15, atk: 7, mana: 1, moves: 1 },
-        null,
-        { hp: 5, atk: 5, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-      ],
-      [
-        { hp: 7, atk: 3, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
+    await endTurn(program, gameKP.publicKey, p1, p2, 4, [
+      boardRow({ 1: card(2, 1, 1, 1, { ...taunt, ...hurt(4) }) }),
+      boardRow({ 0: card(3, 2, 1, 1), 6: card(2, 2, 1, 1, hurt(3)) }),
     ]);
 
-    console.log("Turn 6: Player 2 attacks player 1 hero");
+    console.log("Turn 4: Player 2 attacks player 1's unit and loses its own");
     await attack(
       program,
       gameKP.publicKey,
       p2,
-      7,
       1,
-      [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 0 },
-          null,
-          { hp: 5, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 7, atk: 3, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 1 },
-        ],
-      ],
-      [16, 10]
+      unitAt(6),
+      [boardRow({}), boardRow({ 0: card(3, 2, 1, 1), 6: card(1, 2, 1, 1, hurt(3)) })],
+      [30, 30]
     );
 
-    console.log("Player 2 attacks player 1 unit");
-    await attack(
+    console.log("Player 2 plays two cards");
+    await playCard(
       program,
       gameKP.publicKey,
       p2,
       0,
       3,
+      4,
+      { active: {} },
       [
-        [
-          null,
-          { hp: 15, atk: 7, mana: 1, moves: 0 },
-          null,
-          { hp: 2, atk: 5, mana: 2, moves: 0 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 2, atk: 3, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 10, atk: 10, mana: 1, moves: 1 },
-        ],
+        boardRow({ 3: card(4, 1, 1, 0, { ...taunt, ...sick }) }),
+        boardRow({ 0: card(3, 2, 1, 1), 6: card(1, 2, 1, 1, hurt(3)) }),
       ],
-      [16, 10]
+      [3, 1]
     );
-
-    console.log("Player 2 ends turn");
-    await endTurn(program, gameKP.publicKey, p2, 7, [
-      [
-        null,
-        { hp: 15, atk: 7, mana: 1, moves: 1 },
-        null,
-        { hp: 2, atk: 5, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-      ],
-      [
-        { hp: 2, atk: 3, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 10, atk: 10, mana: 1, moves: 1 },
-      ],
-    ]);
-
-    console.log("Turn 7: Player 1 attacks player 2's unit");
-    await attack(
+    await playCard(
       program,
       gameKP.publicKey,
-      p1,
-      6,
-      1,
+      p2,
+      0,
+      4,
+      4,
+      { active: {} },
       [
-        [
-          null,
-          { hp: 5, atk: 7, mana: 1, moves: 1 },
-          null,
-          { hp: 2, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 2, atk: 3, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 3, atk: 10, mana: 1, moves: 0 },
-        ],
+        boardRow({
+          3: card(4, 1, 1, 0, { ...taunt, ...sick }),
+          4: card(4, 1, 1, 0, { ...taunt, ...sick }),
+        }),
+        boardRow({ 0: card(3, 2, 1, 1), 6: card(1, 2, 1, 1, hurt(3)) }),
       ],
-      [16, 10]
+      [3, 0]
     );
 
-    await endTurn(program, gameKP.publicKey, p1, 8, [
-      [
-        null,
-        { hp: 5, atk: 7, mana: 1, moves: 1 },
-        null,
-        { hp: 2, atk: 5, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-      ],
-      [
-        { hp: 2, atk: 3, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 3, atk: 10, mana: 1, moves: 1 },
-      ],
+    console.log("Player 2 ends turn");
+    await endTurn(program, gameKP.publicKey, p2, p1, 5, [
+      boardRow({ 3: card(4, 1, 1, 1, taunt), 4: card(4, 1, 1, 1, taunt) }),
+      boardRow({ 0: card(3, 2, 1, 1), 6: card(1, 2, 1, 1, hurt(3)) }),
     ]);
 
-    console.log("Turn 8: Player 2 attacks player 1's hero");
-    await attack(
-      program,
-      gameKP.publicKey,
-      p2,
-      7,
-      1,
-      [
-        [
-          null,
-          { hp: 5, atk: 7, mana: 1, moves: 0 },
-          null,
-          { hp: 2, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 2, atk: 3, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 3, atk: 10, mana: 1, moves: 1 },
-        ],
-      ],
-      [9, 10]
-    );
+    console.log("Player 1 plays out the game while player 2 passes");
+    for (let i = 0; i < 100; i++) {
+      let gameState = await program.account.game.fetch(gameKP.publicKey);
+      if (!gameState.state.active) {
+        break;
+      }
 
-    await endTurn(program, gameKP.publicKey, p2, 9, [
-      [
-        null,
-        { hp: 5, atk: 7, mana: 1, moves: 1 },
-        null,
-        { hp: 2, atk: 5, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-      ],
-      [
-        { hp: 2, atk: 3, mana: 2, moves: 1 },
-        null,
-        null,
-        null,
-        null,
-        null,
-        { hp: 3, atk: 10, mana: 1, moves: 1 },
-      ],
-    ]);
+      if (gameState.turn % 2 == 0) {
+        await passTurn(program, gameKP.publicKey, p2, p1);
+        continue;
+      }
 
-    console.log("Turn 9: Player 1 attacks player 2 hero");
-    await attack(
-      program,
-      gameKP.publicKey,
-      p1,
-      6,
-      7,
-      [
-        [
-          null,
-          { hp: 5, atk: 7, mana: 1, moves: 1 },
-          null,
-          { hp: 2, atk: 5, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-        ],
-        [
-          { hp: 2, atk: 3, mana: 2, moves: 1 },
-          null,
-          null,
-          null,
-          null,
-          null,
-          { hp: 3, atk: 10, mana: 1, moves: 0 },
-        ],
-      ],
-      [9, 0]
-    );
+      let pos = gameState.board[1].findIndex((tile) => tile == null);
+      while (
+        gameState.mana[0] >= footman.mana &&
+        gameState.p1Hand.length > 0 &&
+        pos != -1
+      ) {
+        await program.methods
+          .playCard(pos, 0, null)
+          .accounts({ player: p1.publicKey, game: gameKP.publicKey })
+          .rpc();
+        gameState = await program.account.game.fetch(gameKP.publicKey);
+        pos = gameState.board[1].findIndex((tile) => tile == null);
+      }
+
+      for (let j = 0; j < 7; j++) {
+        const unit = gameState.board[1][j];
+        if (!gameState.state.active || !unit || unit.moves == 0) {
+          continue;
+        }
+        const tauntPos = gameState.board[0].findIndex(
+          (tile) => tile != null && tile.taunt
+        );
+        await program.methods
+          .attack(j, tauntPos == -1 ? HERO : unitAt(tauntPos))
+          .accounts({ player: p1.publicKey, game: gameKP.publicKey })
+          .rpc();
+        gameState = await program.account.game.fetch(gameKP.publicKey);
+      }
+
+      if (gameState.state.active) {
+        await passTurn(program, gameKP.publicKey, p1, p2);
+      }
+    }
+
+    const finalState = await program.account.game.fetch(gameKP.publicKey);
+    expect(finalState.state).to.eql({ won: { winner: p1.publicKey } });
+    expect(finalState.health[1]).to.be.at.most(0);
 
     try {
-      await endTurn(program, gameKP.publicKey, p1, null, null);
+      await endTurn(program, gameKP.publicKey, p1, p2, null, null);
     } catch (error) {
       console.log("Expect game over: ", error.error["errorCode"]);
     }
@@ -1006,14 +1182,68 @@ describe("card", () => {
         p1Stats: p1PDA,
         p2Stats: p2PDA,
         game: gameKP.publicKey,
+        leaderboard: leaderboardPDA,
+        season: seasonPDA,
       })
       .rpc();
 
     const post_p1_stats = await program.account.userStats.fetch(p1PDA);
     const post_p2_stats = await program.account.userStats.fetch(p2PDA);
 
-    expect(pre_p1_stats.score + 5).to.eql(post_p1_stats.score);
-    expect(pre_p2_stats.score).to.eql(post_p2_stats.score);
+    expect(post_p1_stats.score).to.be.greaterThan(pre_p1_stats.score);
+    expect(post_p2_stats.score).to.be.lessThan(pre_p2_stats.score);
+    expect(post_p1_stats.gamesPlayed).to.eql(pre_p1_stats.gamesPlayed + 1);
+    expect(post_p2_stats.gamesPlayed).to.eql(pre_p2_stats.gamesPlayed + 1);
+
+    const leaderboard = await program.account.leaderboard.fetch(leaderboardPDA);
+    const scores = leaderboard.entries.map((entry) => entry.score);
+    expect(scores).to.eql([...scores].sort((a, b) => b - a));
+    const p1Entry = leaderboard.entries.find((entry) =>
+      entry.player.equals(p1.publicKey)
+    );
+    expect(p1Entry.score).to.eql(post_p1_stats.score);
+
+    const firstPage = await program.methods
+      .getLeaderboard(0, 2)
+      .accounts({ leaderboard: leaderboardPDA })
+      .view();
+    expect(firstPage).to.eql(leaderboard.entries.slice(0, 2));
+    expect((await program.account.game.fetch(gameKP.publicKey)).settled).to.be
+      .true;
+
+    try {
+      await program.methods
+        .setScores()
+        .accounts({
+          p1: p1.publicKey,
+          p2: p2.publicKey,
+          p1Stats: p1PDA,
+          p2Stats: p2PDA,
+          game: gameKP.publicKey,
+          leaderboard: leaderboardPDA,
+          season: seasonPDA,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("Expect game already settled:", error.error["errorCode"]);
+    }
+    expect((await program.account.userStats.fetch(p1PDA)).score).to.eql(
+      post_p1_stats.score
+    );
+
+    console.log("Test closing the settled game");
+    await program.methods
+      .closeGame()
+      .accounts({
+        game: gameKP.publicKey,
+        p1: p1.publicKey,
+        player: p1.publicKey,
+      })
+      .rpc();
+
+    expect(
+      await program.provider.connection.getAccountInfo(gameKP.publicKey)
+    ).to.be.null;
 
     // console.log("Setting up game between player 2 and player 3");
     // try {